@@ -12,6 +12,11 @@ pub struct Model {
     pub created: DateTime,
     pub user_id: Uuid,
     pub token: String,
+    pub preview_token: String,
+    pub private_repos: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub private_repo_allowlist: Json,
+    pub settings_token: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::plugin::{ActivityResponse, DataPlugin, DataQuery, PluginError, PluginIdentifier};
+
+use crate::api::{graphql::GraphqlApi, rest::RestApi};
+
+/// Maximum number of events returned when no `limit` parameter is given.
+const DEFAULT_LIMIT: usize = 30;
+
+/// The contribution calendar and recent events shown on the user's profile.
+pub struct ActivityProfile {
+    rest_api: Arc<RestApi>,
+    graphql_api: Arc<GraphqlApi>,
+}
+
+impl ActivityProfile {
+    pub fn new(rest_api: &Arc<RestApi>, graphql_api: &Arc<GraphqlApi>) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+            graphql_api: Arc::clone(graphql_api),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for ActivityProfile {
+    type D = ActivityResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let limit = query
+            .get("limit")
+            .map(|limit| {
+                limit.parse().map_err(|_| {
+                    PluginError::InvalidParameter(
+                        "limit".to_string(),
+                        format!("expected a number, found `{limit}`"),
+                    )
+                })
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_LIMIT);
+
+        let (calendar, events) = futures::try_join!(
+            self.graphql_api
+                .contributions
+                .calendar(auth_token, username),
+            self.rest_api.user.list_events(auth_token, username),
+        )?;
+
+        Ok(ActivityResponse {
+            calendar: calendar.into(),
+            events: events
+                .into_iter()
+                .filter_map(|event| event.into_activity())
+                .take(limit)
+                .collect(),
+        })
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("profile")
+    }
+
+    fn params(&self) -> &[&str] {
+        &["limit"]
+    }
+}
@@ -0,0 +1,155 @@
+use reqwest::{header, Client, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::api::GithubApiError;
+
+use super::{Connection, GraphqlQuery, RateLimit};
+
+#[derive(Debug, Deserialize)]
+pub struct GraphqlErrorResponse {
+    pub message: String,
+    /// Category of the error, such as `NOT_FOUND` or `RATE_LIMITED`.
+    #[serde(rename = "type")]
+    pub r#type: Option<String>,
+    /// Path of the field that caused the error.
+    #[serde(default)]
+    pub path: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<WithRateLimit<T>>,
+    #[serde(default)]
+    errors: Vec<GraphqlErrorResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WithRateLimit<T> {
+    #[serde(flatten)]
+    data: T,
+    rate_limit: Option<RateLimit>,
+}
+
+/// Data returned by a query, along with its cost.
+pub struct GraphqlResult<T> {
+    pub data: T,
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Clone)]
+pub struct GraphqlClient {
+    client: Client,
+    api_url: Url,
+}
+
+impl GraphqlClient {
+    pub fn new(client: &Client, api_url: &Url) -> Self {
+        Self {
+            client: client.clone(),
+            api_url: api_url.clone(),
+        }
+    }
+
+    /// Sends a query, returning its data. Errors reported by the API take priority over any
+    /// partial data returned alongside them.
+    pub async fn execute<T: DeserializeOwned>(
+        &self,
+        access_token: &str,
+        query: &GraphqlQuery,
+    ) -> Result<GraphqlResult<T>, GithubApiError> {
+        let response = self
+            .client
+            .post(self.api_url.clone())
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .json(query)
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        let response = response
+            .json::<GraphqlResponse<T>>()
+            .await
+            .map_err(GithubApiError::Response)?;
+
+        if !response.errors.is_empty() {
+            return Err(GithubApiError::from_graphql_errors(response.errors));
+        }
+
+        let data = response.data.ok_or_else(|| {
+            GithubApiError::Graphql(vec!["response contained no data".to_string()])
+        })?;
+
+        if let Some(rate_limit) = &data.rate_limit {
+            debug!(
+                message = "graphql query",
+                cost = rate_limit.cost,
+                node_count = rate_limit.node_count,
+                remaining = rate_limit.remaining
+            );
+            if rate_limit.is_low() {
+                warn!(
+                    message = "graphql rate limit running low",
+                    remaining = rate_limit.remaining,
+                    limit = rate_limit.limit,
+                    reset_at = rate_limit.reset_at
+                );
+            }
+        }
+
+        Ok(GraphqlResult {
+            data: data.data,
+            rate_limit: data.rate_limit,
+        })
+    }
+
+    /// Follows a connection through every page, up to a maximum number of pages. The query is
+    /// built with the cursor of the page to fetch, starting with `None`, and the connection is
+    /// then taken from its data.
+    pub async fn paginate<D, T>(
+        &self,
+        access_token: &str,
+        max_pages: usize,
+        query: impl Fn(Option<&str>) -> GraphqlQuery,
+        connection: impl Fn(D) -> Result<Connection<T>, GithubApiError>,
+    ) -> Result<Vec<T>, GithubApiError>
+    where
+        D: DeserializeOwned,
+    {
+        let mut nodes = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        let mut cost = 0;
+
+        while pages < max_pages {
+            let result = self
+                .execute::<D>(access_token, &query(cursor.as_deref()))
+                .await?;
+            let page = connection(result.data)?;
+            pages += 1;
+            cost += result
+                .rate_limit
+                .map(|rate_limit| rate_limit.cost)
+                .unwrap_or(0);
+
+            cursor = page.next_cursor().map(str::to_string);
+            nodes.extend(page.nodes);
+
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        debug!(
+            message = "graphql pagination",
+            pages,
+            cost,
+            nodes = nodes.len()
+        );
+
+        Ok(nodes)
+    }
+}
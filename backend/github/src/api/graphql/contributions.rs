@@ -0,0 +1,113 @@
+use serde::Deserialize;
+use shared::plugin::{ContributionCalendarResponse, ContributionDayResponse};
+
+use crate::api::GithubApiError;
+
+use super::{GraphqlClient, GraphqlQuery};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionDayApiResponse {
+    pub date: String,
+    pub contribution_count: usize,
+    /// One of `NONE`, `FIRST_QUARTILE`, `SECOND_QUARTILE`, `THIRD_QUARTILE` or
+    /// `FOURTH_QUARTILE`.
+    pub contribution_level: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionWeekResponse {
+    pub contribution_days: Vec<ContributionDayApiResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionCalendarApiResponse {
+    pub total_contributions: usize,
+    pub weeks: Vec<ContributionWeekResponse>,
+}
+
+impl From<ContributionCalendarApiResponse> for ContributionCalendarResponse {
+    fn from(calendar: ContributionCalendarApiResponse) -> Self {
+        Self {
+            total: calendar.total_contributions,
+            days: calendar
+                .weeks
+                .into_iter()
+                .flat_map(|week| week.contribution_days)
+                .map(|day| ContributionDayResponse {
+                    level: match day.contribution_level.as_str() {
+                        "FIRST_QUARTILE" => 1,
+                        "SECOND_QUARTILE" => 2,
+                        "THIRD_QUARTILE" => 3,
+                        "FOURTH_QUARTILE" => 4,
+                        _ => 0,
+                    },
+                    date: day.date,
+                    count: day.contribution_count,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ContributionsData {
+    user: Option<UserContributions>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserContributions {
+    contributions_collection: ContributionsCollection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContributionsCollection {
+    contribution_calendar: ContributionCalendarApiResponse,
+}
+
+pub struct ContributionsApi {
+    client: GraphqlClient,
+}
+
+impl ContributionsApi {
+    pub fn new(client: &GraphqlClient) -> Self {
+        Self {
+            client: client.clone(),
+        }
+    }
+
+    /// Gets a user's contribution calendar for the last year.
+    pub async fn calendar(
+        &self,
+        access_token: &str,
+        login: &str,
+    ) -> Result<ContributionCalendarApiResponse, GithubApiError> {
+        let query = GraphqlQuery::builder("ContributionCalendar")
+            .variable("login", "String!", login)
+            .select(
+                "user(login: $login) {
+                  contributionsCollection {
+                    contributionCalendar {
+                      totalContributions
+                      weeks { contributionDays { date contributionCount contributionLevel } }
+                    }
+                  }
+                }",
+            )
+            .build();
+
+        Ok(self
+            .client
+            .execute::<ContributionsData>(access_token, &query)
+            .await?
+            .data
+            .user
+            .ok_or(GithubApiError::NotFound)?
+            .contributions_collection
+            .contribution_calendar)
+    }
+}
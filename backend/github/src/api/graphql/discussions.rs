@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{api::GithubApiError, posts::raw::RawPost};
+
+use super::{
+    Connection, GraphqlClient, GraphqlQuery, LoginResponse, NameResponse, NodesResponse,
+    TotalCountResponse, MAX_PAGE_SIZE, PAGE_INFO_SELECTION,
+};
+
+/// Maximum number of pages of discussions fetched for a repository.
+const MAX_PAGES: usize = 5;
+
+#[derive(Deserialize)]
+pub struct ReactionGroupResponse {
+    /// Reaction such as `THUMBS_UP` or `HEART`.
+    pub content: String,
+    pub reactors: TotalCountResponse,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionResponse {
+    pub number: usize,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// `None` if the author's account has been deleted.
+    pub author: Option<LoginResponse>,
+    pub category: NameResponse,
+    pub labels: Option<NodesResponse<NameResponse>>,
+    pub reaction_groups: Vec<ReactionGroupResponse>,
+}
+
+impl From<DiscussionResponse> for RawPost {
+    fn from(discussion: DiscussionResponse) -> Self {
+        Self {
+            number: Some(discussion.number),
+            title: discussion.title,
+            body: discussion.body,
+            labels: discussion
+                .labels
+                .map(|labels| labels.nodes.into_iter().map(|label| label.name).collect())
+                .unwrap_or_default(),
+            category: Some(discussion.category.name),
+            reactions: Some(
+                discussion
+                    .reaction_groups
+                    .into_iter()
+                    .filter(|group| group.reactors.total_count > 0)
+                    .map(|group| (group.content.to_lowercase(), group.reactors.total_count))
+                    .collect::<BTreeMap<_, _>>(),
+            ),
+            created_at: discussion.created_at,
+            updated_at: discussion.updated_at,
+            original_link: discussion.url,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DiscussionsData {
+    repository: Option<RepositoryDiscussions>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryDiscussions {
+    discussions: Connection<DiscussionResponse>,
+}
+
+pub struct DiscussionsApi {
+    client: GraphqlClient,
+}
+
+impl DiscussionsApi {
+    pub fn new(client: &GraphqlClient) -> Self {
+        Self {
+            client: client.clone(),
+        }
+    }
+
+    /// Lists the discussions in a repository, newest first.
+    pub async fn list(
+        &self,
+        access_token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<DiscussionResponse>, GithubApiError> {
+        self.client
+            .paginate(
+                access_token,
+                MAX_PAGES,
+                |cursor| {
+                    GraphqlQuery::builder("ListDiscussions")
+                        .variable("owner", "String!", owner)
+                        .variable("name", "String!", repo)
+                        .variable("cursor", "String", cursor)
+                        .select(&format!(
+                            "repository(owner: $owner, name: $name) {{
+                              discussions(
+                                first: {MAX_PAGE_SIZE}
+                                after: $cursor
+                                orderBy: {{ field: CREATED_AT, direction: DESC }}
+                              ) {{
+                                nodes {{
+                                  number title body url createdAt updatedAt
+                                  author {{ login }}
+                                  category {{ name }}
+                                  labels(first: 100) {{ nodes {{ name }} }}
+                                  reactionGroups {{ content reactors {{ totalCount }} }}
+                                }}
+                                {PAGE_INFO_SELECTION}
+                              }}
+                            }}"
+                        ))
+                        .build()
+                },
+                |data: DiscussionsData| {
+                    Ok(data.repository.ok_or(GithubApiError::NotFound)?.discussions)
+                },
+            )
+            .await
+    }
+}
@@ -0,0 +1,64 @@
+mod client;
+mod contributions;
+mod discussions;
+mod pagination;
+mod pinned_items;
+mod profile;
+mod pull_requests;
+mod query;
+mod rate_limit;
+
+use reqwest::{Client, Url};
+use serde::Deserialize;
+
+pub use client::*;
+pub use contributions::*;
+pub use discussions::*;
+pub use pagination::*;
+pub use pinned_items::*;
+pub use profile::*;
+pub use pull_requests::*;
+pub use query::*;
+pub use rate_limit::*;
+
+/// A connection where only the nodes are needed.
+#[derive(Clone, Deserialize)]
+pub struct NodesResponse<T> {
+    pub nodes: Vec<T>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct NameResponse {
+    pub name: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct LoginResponse {
+    pub login: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotalCountResponse {
+    pub total_count: usize,
+}
+
+pub struct GraphqlApi {
+    pub contributions: ContributionsApi,
+    pub discussions: DiscussionsApi,
+    pub profile: ProfileApi,
+    pub pull_requests: PullRequestsApi,
+}
+
+impl GraphqlApi {
+    pub fn new(client: &Client, api_url: &Url) -> Self {
+        let client = GraphqlClient::new(client, api_url);
+
+        Self {
+            contributions: ContributionsApi::new(&client),
+            discussions: DiscussionsApi::new(&client),
+            profile: ProfileApi::new(&client),
+            pull_requests: PullRequestsApi::new(&client),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use serde::Deserialize;
+
+/// Selection of the page information needed to follow a connection, to be included alongside
+/// its `nodes`.
+pub const PAGE_INFO_SELECTION: &str = "pageInfo { hasNextPage endCursor }";
+
+/// Largest page size accepted by the API.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// A page of a cursor paginated connection.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection<T> {
+    pub nodes: Vec<T>,
+    pub page_info: PageInfo,
+}
+
+impl<T> Connection<T> {
+    /// Cursor of the next page, if there is one.
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.page_info
+            .has_next_page
+            .then_some(self.page_info.end_cursor.as_deref())
+            .flatten()
+    }
+}
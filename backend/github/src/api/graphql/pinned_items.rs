@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use shared::plugin::{LanguageResponse, ProjectResponse, Repo};
+
+use super::{NameResponse, NodesResponse, TotalCountResponse};
+
+/// Maximum number of items that can be pinned to a profile.
+const MAX_PINNED_ITEMS: usize = 6;
+
+/// Selection of the language breakdown of a repository, largest first.
+pub const LANGUAGES_SELECTION: &str =
+    "languages(first: 20, orderBy: { field: SIZE, direction: DESC }) { edges { size node { name } } }";
+
+#[derive(Clone, Deserialize)]
+pub struct TopicResponse {
+    pub topic: NameResponse,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct LanguageEdgeResponse {
+    /// Bytes of code written in the language.
+    pub size: usize,
+    pub node: NameResponse,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct LanguageConnectionResponse {
+    pub edges: Vec<LanguageEdgeResponse>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedRepositoryResponse {
+    pub name: String,
+    pub name_with_owner: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub homepage_url: Option<String>,
+    pub is_private: bool,
+    pub stargazer_count: usize,
+    pub fork_count: usize,
+    pub watchers: TotalCountResponse,
+    pub issues: TotalCountResponse,
+    pub repository_topics: NodesResponse<TopicResponse>,
+    pub languages: Option<LanguageConnectionResponse>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GistFileResponse {
+    pub name: String,
+    pub size: Option<usize>,
+    pub language: Option<NameResponse>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedGistResponse {
+    pub description: Option<String>,
+    pub url: String,
+    pub stargazer_count: usize,
+    pub forks: TotalCountResponse,
+    pub files: Vec<GistFileResponse>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "__typename")]
+pub enum PinnedItemResponse {
+    Repository(PinnedRepositoryResponse),
+    Gist(PinnedGistResponse),
+}
+
+impl From<PinnedItemResponse> for ProjectResponse {
+    fn from(item: PinnedItemResponse) -> Self {
+        match item {
+            PinnedItemResponse::Repository(repository) => Self {
+                name: repository.name,
+                full_name: Some(repository.name_with_owner),
+                private: repository.is_private,
+                description: repository.description,
+                // An empty homepage is returned as an empty string rather than null
+                url: repository.homepage_url.filter(|url| !url.is_empty()),
+                repo: (!repository.is_private).then_some(Repo {
+                    url: repository.url,
+                    stars: repository.stargazer_count,
+                    forks: repository.fork_count,
+                    watchers: repository.watchers.total_count,
+                    issues: repository.issues.total_count,
+                }),
+                tags: repository
+                    .repository_topics
+                    .nodes
+                    .into_iter()
+                    .map(|topic| topic.topic.name)
+                    .collect(),
+                languages: repository.languages.map(|languages| {
+                    LanguageResponse::from_bytes(
+                        languages
+                            .edges
+                            .into_iter()
+                            .map(|edge| (edge.node.name, edge.size)),
+                    )
+                }),
+                latest_release: None,
+            },
+            PinnedItemResponse::Gist(gist) => {
+                let languages =
+                    LanguageResponse::from_bytes(gist.files.iter().filter_map(|file| {
+                        Some((file.language.as_ref()?.name.clone(), file.size.unwrap_or(0)))
+                    }));
+
+                Self {
+                    // Gists are named after their first file
+                    name: gist
+                        .files
+                        .into_iter()
+                        .next()
+                        .map(|file| file.name)
+                        .unwrap_or_else(|| gist.url.clone()),
+                    full_name: None,
+                    // Secret gists can't be pinned
+                    private: false,
+                    description: gist
+                        .description
+                        .filter(|description| !description.is_empty()),
+                    url: Some(gist.url.clone()),
+                    repo: Some(Repo {
+                        url: gist.url,
+                        stars: gist.stargazer_count,
+                        forks: gist.forks.total_count,
+                        watchers: 0,
+                        issues: 0,
+                    }),
+                    tags: Vec::new(),
+                    languages: (!languages.is_empty()).then_some(languages),
+                    latest_release: None,
+                }
+            }
+        }
+    }
+}
+
+/// Selection of the repositories and gists pinned to a user's profile, in pinned order, to be
+/// included within a `User`.
+pub fn pinned_items_selection() -> String {
+    format!(
+        "pinnedItems(first: {MAX_PINNED_ITEMS}, types: [REPOSITORY, GIST]) {{
+          nodes {{
+            __typename
+            ... on Repository {{
+              name nameWithOwner description url homepageUrl isPrivate stargazerCount forkCount
+              watchers {{ totalCount }}
+              issues(states: OPEN) {{ totalCount }}
+              repositoryTopics(first: 20) {{ nodes {{ topic {{ name }} }} }}
+              {LANGUAGES_SELECTION}
+            }}
+            ... on Gist {{
+              description url stargazerCount
+              forks {{ totalCount }}
+              files {{ name size language {{ name }} }}
+            }}
+          }}
+        }}"
+    )
+}
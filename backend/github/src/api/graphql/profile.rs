@@ -0,0 +1,242 @@
+use serde::Deserialize;
+use shared::plugin::LanguageResponse;
+
+use crate::api::{
+    rest::{LicenseResponse, OwnerResponse, RepositoryResponse, UserResponse},
+    GithubApiError,
+};
+
+use super::{
+    pinned_items_selection, Connection, GraphqlClient, GraphqlQuery, LanguageConnectionResponse,
+    NameResponse, NodesResponse, PinnedItemResponse, TopicResponse, TotalCountResponse,
+    LANGUAGES_SELECTION, MAX_PAGE_SIZE, PAGE_INFO_SELECTION,
+};
+
+/// Maximum number of pages of repositories fetched for a user.
+const MAX_REPOSITORY_PAGES: usize = 5;
+
+/// Repositories the user owns, collaborates on, or can access through an organisation, matching
+/// those listed by the REST API.
+const REPOSITORIES_ARGUMENTS: &str = "affiliations: [OWNER, COLLABORATOR, ORGANIZATION_MEMBER]
+    ownerAffiliations: [OWNER, COLLABORATOR, ORGANIZATION_MEMBER]
+    orderBy: { field: NAME, direction: ASC }";
+
+fn repositories_selection() -> String {
+    format!(
+        "repositories(first: {MAX_PAGE_SIZE}, after: $cursor, {REPOSITORIES_ARGUMENTS}) {{
+          nodes {{
+            name nameWithOwner url description homepageUrl
+            isPrivate isFork isArchived stargazerCount forkCount pushedAt createdAt
+            owner {{ login }}
+            watchers {{ totalCount }}
+            issues(states: OPEN) {{ totalCount }}
+            repositoryTopics(first: 20) {{ nodes {{ topic {{ name }} }} }}
+            primaryLanguage {{ name }}
+            defaultBranchRef {{ name }}
+            licenseInfo {{ spdxId name }}
+            {LANGUAGES_SELECTION}
+          }}
+          {PAGE_INFO_SELECTION}
+        }}"
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseInfoResponse {
+    spdx_id: Option<String>,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryNodeResponse {
+    name: String,
+    name_with_owner: String,
+    url: String,
+    description: Option<String>,
+    homepage_url: Option<String>,
+    is_private: bool,
+    is_fork: bool,
+    is_archived: bool,
+    stargazer_count: usize,
+    fork_count: usize,
+    pushed_at: Option<String>,
+    created_at: Option<String>,
+    owner: OwnerResponse,
+    watchers: TotalCountResponse,
+    issues: TotalCountResponse,
+    repository_topics: NodesResponse<TopicResponse>,
+    primary_language: Option<NameResponse>,
+    default_branch_ref: Option<NameResponse>,
+    license_info: Option<LicenseInfoResponse>,
+    languages: Option<LanguageConnectionResponse>,
+}
+
+/// A repository along with its language breakdown, which the REST API lists separately.
+pub struct ProfileRepositoryResponse {
+    pub repository: RepositoryResponse,
+    pub languages: Vec<LanguageResponse>,
+}
+
+impl From<RepositoryNodeResponse> for ProfileRepositoryResponse {
+    fn from(node: RepositoryNodeResponse) -> Self {
+        Self {
+            repository: RepositoryResponse {
+                name: node.name,
+                full_name: node.name_with_owner,
+                owner: node.owner,
+                private: node.is_private,
+                fork: node.is_fork,
+                archived: node.is_archived,
+                html_url: node.url,
+                description: node.description,
+                forks_count: node.fork_count,
+                stargazers_count: node.stargazer_count,
+                watchers_count: node.watchers.total_count,
+                open_issues_count: node.issues.total_count,
+                topics: node
+                    .repository_topics
+                    .nodes
+                    .into_iter()
+                    .map(|topic| topic.topic.name)
+                    .collect(),
+                // An empty homepage is returned as an empty string rather than null
+                homepage: node.homepage_url.filter(|url| !url.is_empty()),
+                language: node.primary_language.map(|language| language.name),
+                pushed_at: node.pushed_at,
+                created_at: node.created_at,
+                default_branch: node.default_branch_ref.map(|branch| branch.name),
+                license: node.license_info.map(|license| LicenseResponse {
+                    spdx_id: license.spdx_id,
+                    name: license.name,
+                }),
+            },
+            languages: node
+                .languages
+                .map(|languages| {
+                    LanguageResponse::from_bytes(
+                        languages
+                            .edges
+                            .into_iter()
+                            .map(|edge| (edge.node.name, edge.size)),
+                    )
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Profile of the authenticated user, along with their repositories and pinned items.
+pub struct ProfileResponse {
+    pub user: UserResponse,
+    pub repositories: Vec<ProfileRepositoryResponse>,
+    pub pinned_items: Vec<PinnedItemResponse>,
+}
+
+#[derive(Deserialize)]
+struct ProfileData {
+    viewer: ViewerProfile,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ViewerProfile {
+    login: String,
+    avatar_url: String,
+    url: String,
+    name: Option<String>,
+    company: Option<String>,
+    website_url: Option<String>,
+    location: Option<String>,
+    email: Option<String>,
+    bio: Option<String>,
+    twitter_username: Option<String>,
+    pinned_items: NodesResponse<PinnedItemResponse>,
+    repositories: Connection<RepositoryNodeResponse>,
+}
+
+#[derive(Deserialize)]
+struct RepositoriesData {
+    viewer: ViewerRepositories,
+}
+
+#[derive(Deserialize)]
+struct ViewerRepositories {
+    repositories: Connection<RepositoryNodeResponse>,
+}
+
+pub struct ProfileApi {
+    client: GraphqlClient,
+}
+
+impl ProfileApi {
+    pub fn new(client: &GraphqlClient) -> Self {
+        Self {
+            client: client.clone(),
+        }
+    }
+
+    /// Gets the profile, repositories and pinned items of the authenticated user. Everything
+    /// needed to render a profile is fetched in a single request, unless the user has more
+    /// repositories than fit on a page.
+    pub async fn get(&self, access_token: &str) -> Result<ProfileResponse, GithubApiError> {
+        let query = GraphqlQuery::builder("GetProfile")
+            .variable("cursor", "String", None::<String>)
+            .select(&format!(
+                "viewer {{
+                  login avatarUrl url name company websiteUrl location email bio twitterUsername
+                  {}
+                  {}
+                }}",
+                pinned_items_selection(),
+                repositories_selection()
+            ))
+            .build();
+
+        let viewer = self
+            .client
+            .execute::<ProfileData>(access_token, &query)
+            .await?
+            .data
+            .viewer;
+
+        let cursor = viewer.repositories.next_cursor().map(str::to_string);
+        let mut repositories = viewer.repositories.nodes;
+        if let Some(cursor) = cursor {
+            repositories.extend(
+                self.client
+                    .paginate(
+                        access_token,
+                        MAX_REPOSITORY_PAGES - 1,
+                        |next| {
+                            GraphqlQuery::builder("ListRepositories")
+                                .variable("cursor", "String", next.unwrap_or(&cursor))
+                                .select(&format!("viewer {{ {} }}", repositories_selection()))
+                                .build()
+                        },
+                        |data: RepositoriesData| Ok(data.viewer.repositories),
+                    )
+                    .await?,
+            );
+        }
+
+        Ok(ProfileResponse {
+            user: UserResponse {
+                login: viewer.login,
+                avatar_url: viewer.avatar_url,
+                html_url: viewer.url,
+                name: viewer.name,
+                company: viewer.company,
+                // Unset values are returned as empty strings rather than null
+                blog: viewer.website_url.filter(|url| !url.is_empty()),
+                location: viewer.location,
+                email: viewer.email.filter(|email| !email.is_empty()),
+                bio: viewer.bio,
+                twitter_username: viewer.twitter_username,
+            },
+            repositories: repositories.into_iter().map(Into::into).collect(),
+            pinned_items: viewer.pinned_items.nodes,
+        })
+    }
+}
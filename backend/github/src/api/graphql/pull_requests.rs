@@ -0,0 +1,56 @@
+use serde::Deserialize;
+
+use crate::api::GithubApiError;
+
+use super::{GraphqlClient, GraphqlQuery, TotalCountResponse};
+
+#[derive(Deserialize)]
+struct OpenPullRequestsData {
+    repository: Option<RepositoryPullRequests>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryPullRequests {
+    pull_requests: TotalCountResponse,
+}
+
+pub struct PullRequestsApi {
+    client: GraphqlClient,
+}
+
+impl PullRequestsApi {
+    pub fn new(client: &GraphqlClient) -> Self {
+        Self {
+            client: client.clone(),
+        }
+    }
+
+    /// Counts the open pull requests of a repository.
+    pub async fn count_open(
+        &self,
+        access_token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<usize, GithubApiError> {
+        let query = GraphqlQuery::builder("CountOpenPullRequests")
+            .variable("owner", "String!", owner)
+            .variable("name", "String!", repo)
+            .select(
+                "repository(owner: $owner, name: $name) {
+                  pullRequests(states: OPEN) { totalCount }
+                }",
+            )
+            .build();
+
+        Ok(self
+            .client
+            .execute::<OpenPullRequestsData>(access_token, &query)
+            .await?
+            .data
+            .repository
+            .ok_or(GithubApiError::NotFound)?
+            .pull_requests
+            .total_count)
+    }
+}
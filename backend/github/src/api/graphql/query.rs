@@ -0,0 +1,75 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::RATE_LIMIT_SELECTION;
+
+/// A GraphQL query ready to be sent, along with the values of its variables.
+#[derive(Debug, Serialize)]
+pub struct GraphqlQuery {
+    query: String,
+    variables: Map<String, Value>,
+}
+
+impl GraphqlQuery {
+    pub fn builder(name: &str) -> QueryBuilder {
+        QueryBuilder::new(name)
+    }
+}
+
+/// Builds a named query from top level selections, declaring the variables they use. Several
+/// selections can be combined so that related data is fetched in a single request, and the rate
+/// limit is always selected so that the cost of the query can be reported.
+pub struct QueryBuilder {
+    name: String,
+    variables: Vec<(String, String)>,
+    values: Map<String, Value>,
+    selections: Vec<String>,
+}
+
+impl QueryBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            variables: Vec::new(),
+            values: Map::new(),
+            selections: Vec::new(),
+        }
+    }
+
+    /// Declares a variable with a GraphQL type such as `String!`, along with its value.
+    pub fn variable(mut self, name: &str, r#type: &str, value: impl Into<Value>) -> Self {
+        self.variables.push((name.to_string(), r#type.to_string()));
+        self.values.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Adds a top level selection, such as `viewer { login }`.
+    pub fn select(mut self, selection: &str) -> Self {
+        self.selections.push(selection.trim().to_string());
+        self
+    }
+
+    pub fn build(self) -> GraphqlQuery {
+        let variables = if self.variables.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "({})",
+                self.variables
+                    .iter()
+                    .map(|(name, r#type)| format!("${name}: {type}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+
+        GraphqlQuery {
+            query: format!(
+                "query {}{variables} {{\n{}\n{RATE_LIMIT_SELECTION}\n}}",
+                self.name,
+                self.selections.join("\n"),
+            ),
+            variables: self.values,
+        }
+    }
+}
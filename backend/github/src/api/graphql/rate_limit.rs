@@ -0,0 +1,25 @@
+use serde::Deserialize;
+
+/// Selection added to every query to report its cost.
+pub const RATE_LIMIT_SELECTION: &str = "rateLimit { cost nodeCount limit remaining resetAt }";
+
+/// Proportion of the rate limit remaining below which a warning is logged.
+const LOW_REMAINING: f64 = 0.1;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// Points deducted from the rate limit by the query.
+    pub cost: usize,
+    /// Number of nodes the query could return.
+    pub node_count: usize,
+    pub limit: usize,
+    pub remaining: usize,
+    pub reset_at: String,
+}
+
+impl RateLimit {
+    pub fn is_low(&self) -> bool {
+        (self.remaining as f64) < self.limit as f64 * LOW_REMAINING
+    }
+}
@@ -1,7 +1,9 @@
+use graphql::GraphqlErrorResponse;
 use reqwest::StatusCode;
 use shared::plugin::PluginError;
 use thiserror::Error;
 
+pub mod graphql;
 pub mod oauth;
 pub mod rest;
 
@@ -21,6 +23,10 @@ pub enum GithubApiError {
     StatusCode(StatusCode),
     #[error("unable to parse response: {0}")]
     Response(reqwest::Error),
+    #[error("rate limit exceeded")]
+    RateLimited,
+    #[error("graphql errors: {}", .0.join(", "))]
+    Graphql(Vec<String>),
 }
 
 impl GithubApiError {
@@ -33,6 +39,40 @@ impl GithubApiError {
             status => Err(GithubApiError::StatusCode(status)),
         }
     }
+
+    /// Maps errors reported in a GraphQL response, using the type of the first error that
+    /// corresponds to a REST status, and otherwise reporting every message.
+    pub fn from_graphql_errors(errors: Vec<GraphqlErrorResponse>) -> Self {
+        let mapped = errors
+            .iter()
+            .find_map(|error| match error.r#type.as_deref()? {
+                "NOT_FOUND" => Some(GithubApiError::NotFound),
+                "FORBIDDEN" | "INSUFFICIENT_SCOPES" => Some(GithubApiError::Forbidden),
+                "RATE_LIMITED" => Some(GithubApiError::RateLimited),
+                _ => None,
+            });
+
+        mapped.unwrap_or_else(|| {
+            GithubApiError::Graphql(
+                errors
+                    .into_iter()
+                    .map(|error| match error.path.is_empty() {
+                        true => error.message,
+                        false => format!(
+                            "{} (at {})",
+                            error.message,
+                            error
+                                .path
+                                .iter()
+                                .map(|segment| segment.to_string().trim_matches('"').to_string())
+                                .collect::<Vec<_>>()
+                                .join(".")
+                        ),
+                    })
+                    .collect(),
+            )
+        })
+    }
 }
 
 impl From<GithubApiError> for PluginError {
@@ -51,6 +91,8 @@ impl From<GithubApiError> for PluginError {
                 }
             }
             GithubApiError::Response(_) => Self::Internal,
+            GithubApiError::RateLimited => Self::External,
+            GithubApiError::Graphql(_) => Self::External,
         }
     }
 }
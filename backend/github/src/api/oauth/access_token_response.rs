@@ -3,6 +3,9 @@ use serde::Deserialize;
 #[derive(Deserialize)]
 pub struct AuthAccessTokenResponse {
     pub access_token: String,
+    // Part of the response, though only the token is read so far
+    #[allow(dead_code)]
     pub scope: String,
+    #[allow(dead_code)]
     pub token_type: String,
 }
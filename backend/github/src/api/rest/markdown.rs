@@ -0,0 +1,50 @@
+use reqwest::{header, Client, Url};
+use serde::Serialize;
+
+use crate::api::GithubApiError;
+
+#[derive(Serialize)]
+struct RenderRequest<'a> {
+    text: &'a str,
+    mode: &'static str,
+    context: &'a str,
+}
+
+pub struct MarkdownApi {
+    client: Client,
+    api_base: Url,
+}
+
+impl MarkdownApi {
+    pub fn new(client: &Client, api_base: &Url) -> Self {
+        Self {
+            client: client.clone(),
+            api_base: api_base.clone(),
+        }
+    }
+
+    /// Renders GitHub Flavored Markdown to HTML. References such as issue numbers are linked
+    /// relative to the `user/repo` context.
+    pub async fn render(
+        &self,
+        access_token: &str,
+        text: &str,
+        context: &str,
+    ) -> Result<String, GithubApiError> {
+        let response = self
+            .client
+            .post(self.api_base.join("markdown")?)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .json(&RenderRequest {
+                text,
+                mode: "gfm",
+                context,
+            })
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response.text().await.map_err(GithubApiError::Response)
+    }
+}
@@ -1,14 +1,20 @@
+mod markdown;
+mod rate_limit;
 mod repositories;
 mod search;
 mod user;
 
 use reqwest::{Client, Url};
 
+pub use markdown::*;
+pub use rate_limit::*;
 pub use repositories::*;
 pub use search::*;
 pub use user::*;
 
 pub struct RestApi {
+    pub markdown: MarkdownApi,
+    pub rate_limit: RateLimitApi,
     pub repositories: RepositoriesApi,
     pub user: UserApi,
     pub search: SearchApi,
@@ -17,6 +23,8 @@ pub struct RestApi {
 impl RestApi {
     pub fn new(client: &Client, api_base: &Url) -> Self {
         Self {
+            markdown: MarkdownApi::new(client, api_base),
+            rate_limit: RateLimitApi::new(client, api_base),
             repositories: RepositoriesApi::new(client, api_base),
             user: UserApi::new(client, api_base),
             search: SearchApi::new(client, api_base),
@@ -0,0 +1,29 @@
+use reqwest::{Client, Url};
+
+use crate::api::GithubApiError;
+
+pub struct RateLimitApi {
+    client: Client,
+    api_base: Url,
+}
+
+impl RateLimitApi {
+    pub fn new(client: &Client, api_base: &Url) -> Self {
+        Self {
+            client: client.clone(),
+            api_base: api_base.clone(),
+        }
+    }
+
+    /// Requests the rate limit status without a token. Requests to this endpoint do not count
+    /// against the rate limit, so it can be used to check that the API is reachable.
+    pub async fn check(&self) -> Result<(), GithubApiError> {
+        let response = self
+            .client
+            .get(self.api_base.join("rate_limit")?)
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())
+    }
+}
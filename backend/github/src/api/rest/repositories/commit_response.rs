@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GitActorResponse {
+    pub date: String,
+}
+
+#[derive(Deserialize)]
+pub struct GitCommitResponse {
+    pub author: Option<GitActorResponse>,
+    pub committer: Option<GitActorResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct CommitResponse {
+    pub commit: GitCommitResponse,
+}
+
+impl CommitResponse {
+    /// Date the commit was authored, falling back to when it was committed.
+    pub fn date(&self) -> Option<&str> {
+        self.commit
+            .author
+            .as_ref()
+            .or(self.commit.committer.as_ref())
+            .map(|actor| actor.date.as_str())
+    }
+}
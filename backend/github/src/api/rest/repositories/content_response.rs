@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ContentResponse {
+    pub name: String,
+    pub path: String,
+    /// One of `file`, `dir`, `symlink` or `submodule`.
+    pub r#type: String,
+    pub html_url: String,
+}
@@ -1,9 +1,17 @@
+mod commit_response;
+mod content_response;
+mod release_response;
 mod repository_response;
 
+use std::collections::HashMap;
+
 use reqwest::{header, Client, Url};
 
-use crate::api::GithubApiError;
-pub use repository_response::RepositoryResponse;
+use crate::api::{rest::LabelResponse, GithubApiError};
+pub use commit_response::CommitResponse;
+pub use content_response::ContentResponse;
+pub use release_response::ReleaseResponse;
+pub use repository_response::{LicenseResponse, OwnerResponse, RepositoryResponse};
 
 pub struct RepositoriesApi {
     client: Client,
@@ -37,6 +45,27 @@ impl RepositoriesApi {
             .map_err(GithubApiError::Response)
     }
 
+    pub async fn get(
+        &self,
+        access_token: &str,
+        user: &str,
+        repo: &str,
+    ) -> Result<RepositoryResponse, GithubApiError> {
+        let response = self
+            .client
+            .get(self.api_base.join(&format!("repos/{user}/{repo}"))?)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<RepositoryResponse>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
     pub async fn get_readme(
         &self,
         access_token: &str,
@@ -62,4 +91,186 @@ impl RepositoriesApi {
 
         response.text().await.map_err(GithubApiError::Response)
     }
+
+    /// Lists the entries of a directory within the default branch of a repository.
+    pub async fn list_contents(
+        &self,
+        access_token: &str,
+        user: &str,
+        repo: &str,
+        path: &str,
+    ) -> Result<Vec<ContentResponse>, GithubApiError> {
+        let response = self
+            .client
+            .get(
+                self.api_base
+                    .join(&format!("repos/{user}/{repo}/contents/{path}"))?,
+            )
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<Vec<ContentResponse>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
+    /// Gets the raw contents of a file within the default branch of a repository.
+    pub async fn get_file(
+        &self,
+        access_token: &str,
+        user: &str,
+        repo: &str,
+        path: &str,
+    ) -> Result<String, GithubApiError> {
+        let response = self
+            .client
+            .get(
+                self.api_base
+                    .join(&format!("repos/{user}/{repo}/contents/{path}"))?,
+            )
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .header(header::ACCEPT, "application/vnd.github.raw")
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response.text().await.map_err(GithubApiError::Response)
+    }
+
+    /// Lists the most recent commits touching a path, newest first.
+    pub async fn list_commits(
+        &self,
+        access_token: &str,
+        user: &str,
+        repo: &str,
+        path: &str,
+    ) -> Result<Vec<CommitResponse>, GithubApiError> {
+        let mut url = self
+            .api_base
+            .join(&format!("repos/{user}/{repo}/commits"))?;
+        url.query_pairs_mut()
+            .append_pair("path", path)
+            .append_pair("per_page", "100");
+
+        let response = self
+            .client
+            .get(url)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<Vec<CommitResponse>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
+    /// Gets the number of bytes of code written in each language within a repository.
+    pub async fn list_languages(
+        &self,
+        access_token: &str,
+        full_name: &str,
+    ) -> Result<HashMap<String, usize>, GithubApiError> {
+        let response = self
+            .client
+            .get(
+                self.api_base
+                    .join(&format!("repos/{full_name}/languages"))?,
+            )
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<HashMap<String, usize>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
+    /// Lists the most recent releases of a repository, newest first, with notes rendered as HTML.
+    pub async fn list_releases(
+        &self,
+        access_token: &str,
+        full_name: &str,
+        count: usize,
+    ) -> Result<Vec<ReleaseResponse>, GithubApiError> {
+        let mut url = self.api_base.join(&format!("repos/{full_name}/releases"))?;
+        url.query_pairs_mut()
+            .append_pair("per_page", &count.to_string());
+
+        let response = self
+            .client
+            .get(url)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .header(header::ACCEPT, "application/vnd.github.html+json")
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<Vec<ReleaseResponse>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
+    /// Gets the release GitHub shows as latest, which is the most recent non-prerelease,
+    /// non-draft release unless another has been marked as latest.
+    pub async fn get_latest_release(
+        &self,
+        access_token: &str,
+        full_name: &str,
+    ) -> Result<ReleaseResponse, GithubApiError> {
+        let response = self
+            .client
+            .get(
+                self.api_base
+                    .join(&format!("repos/{full_name}/releases/latest"))?,
+            )
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .header(header::ACCEPT, "application/vnd.github.html+json")
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<ReleaseResponse>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
+
+    /// Lists the labels defined in a repository.
+    pub async fn list_labels(
+        &self,
+        access_token: &str,
+        user: &str,
+        repo: &str,
+    ) -> Result<Vec<LabelResponse>, GithubApiError> {
+        let mut url = self.api_base.join(&format!("repos/{user}/{repo}/labels"))?;
+        url.query_pairs_mut().append_pair("per_page", "100");
+
+        let response = self
+            .client
+            .get(url)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<Vec<LabelResponse>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
 }
@@ -0,0 +1,48 @@
+use serde::Deserialize;
+use shared::plugin::ReleaseAssetResponse;
+
+#[derive(Deserialize)]
+pub struct ReleaseAssetApiResponse {
+    pub name: String,
+    pub browser_download_url: String,
+    pub content_type: String,
+    pub size: usize,
+    pub download_count: usize,
+}
+
+#[derive(Deserialize)]
+pub struct ReleaseResponse {
+    pub tag_name: String,
+    pub name: Option<String>,
+    /// Only present when requested with the HTML media type.
+    pub body_html: Option<String>,
+    pub html_url: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub published_at: Option<String>,
+    pub assets: Vec<ReleaseAssetApiResponse>,
+}
+
+impl From<ReleaseResponse> for shared::plugin::ReleaseResponse {
+    fn from(release: ReleaseResponse) -> Self {
+        Self {
+            tag: release.tag_name,
+            name: release.name.filter(|name| !name.is_empty()),
+            notes: release.body_html.filter(|notes| !notes.is_empty()),
+            url: release.html_url,
+            prerelease: release.prerelease,
+            published_at: release.published_at,
+            assets: release
+                .assets
+                .into_iter()
+                .map(|asset| ReleaseAssetResponse {
+                    name: asset.name,
+                    url: asset.browser_download_url,
+                    content_type: asset.content_type,
+                    size: asset.size,
+                    download_count: asset.download_count,
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,10 +1,26 @@
 use serde::Deserialize;
 use shared::plugin::{ProjectResponse, Repo};
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
+pub struct LicenseResponse {
+    pub spdx_id: Option<String>,
+    pub name: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OwnerResponse {
+    pub login: String,
+}
+
+#[derive(Clone, Deserialize)]
 pub struct RepositoryResponse {
     pub name: String,
+    /// Name including the owner, such as `octocat/hello-world`.
+    pub full_name: String,
+    pub owner: OwnerResponse,
     pub private: bool,
+    pub fork: bool,
+    pub archived: bool,
     pub html_url: String,
     pub description: Option<String>,
     pub forks_count: usize,
@@ -14,6 +30,10 @@ pub struct RepositoryResponse {
     pub topics: Vec<String>,
     pub homepage: Option<String>,
     pub language: Option<String>,
+    pub pushed_at: Option<String>,
+    pub created_at: Option<String>,
+    pub default_branch: Option<String>,
+    pub license: Option<LicenseResponse>,
 }
 
 impl From<&RepositoryResponse> for Repo {
@@ -32,11 +52,15 @@ impl From<&RepositoryResponse> for ProjectResponse {
     fn from(repository: &RepositoryResponse) -> Self {
         Self {
             name: repository.name.clone(),
+            full_name: Some(repository.full_name.clone()),
+            private: repository.private,
             description: repository.description.clone(),
             url: repository.homepage.clone(),
             repo: (!repository.private).then_some(Repo::from(repository)),
             tags: repository.topics.clone(),
-            languages: repository.language.clone().map(|language| vec![language]),
+            // Filled from the languages endpoint, as only the primary language is included
+            languages: None,
+            latest_release: None,
         }
     }
 }
@@ -2,7 +2,7 @@ use reqwest::{header, Client, Url};
 use serde::Deserialize;
 use shared::plugin::PostResponse;
 
-use crate::api::GithubApiError;
+use crate::{api::GithubApiError, posts::raw::RawPost};
 
 use super::SearchResponse;
 
@@ -16,10 +16,11 @@ pub struct PartialUserResponse {
     pub login: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct LabelResponse {
     pub name: String,
     pub color: String,
+    pub description: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -37,13 +38,15 @@ pub struct IssueResponse {
     pub body: String,
 }
 
-impl From<IssueResponse> for PostResponse {
+impl From<IssueResponse> for RawPost {
     fn from(issue: IssueResponse) -> Self {
-        PostResponse {
-            number: issue.number,
+        Self {
+            number: Some(issue.number),
             title: issue.title,
             body: issue.body,
-            tags: issue.labels.into_iter().map(|label| label.name).collect(),
+            labels: issue.labels.into_iter().map(|label| label.name).collect(),
+            category: None,
+            reactions: None,
             created_at: issue.created_at,
             updated_at: issue.updated_at,
             original_link: issue.html_url,
@@ -51,6 +54,12 @@ impl From<IssueResponse> for PostResponse {
     }
 }
 
+impl From<IssueResponse> for PostResponse {
+    fn from(issue: IssueResponse) -> Self {
+        RawPost::from(issue).into()
+    }
+}
+
 pub struct SearchIssuesBuilder {
     client: Client,
     api_base: Url,
@@ -83,6 +92,8 @@ impl SearchIssuesBuilder {
         self
     }
 
+    // Counterpart to `open`, unused by the current plugins
+    #[allow(dead_code)]
     pub fn closed(mut self) -> Self {
         self.open = Some(false);
         self
@@ -95,7 +106,9 @@ impl SearchIssuesBuilder {
             self.labels.map(|labels| {
                 labels
                     .into_iter()
-                    .map(|label| format!("label:{label}"))
+                    // Quoted so that labels can contain spaces. Labels must not contain
+                    // quotes, which would allow other qualifiers to be added
+                    .map(|label| format!("label:\"{label}\""))
                     .collect::<Vec<_>>()
                     .join(" ")
             }),
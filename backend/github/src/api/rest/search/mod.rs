@@ -6,9 +6,14 @@ use self::{issues::SearchIssuesApi, repositories::SearchRepositoriesApi};
 mod issues;
 mod repositories;
 
+pub use issues::{IssueResponse, LabelResponse};
+
 #[derive(Deserialize)]
 pub struct SearchResponse<T> {
+    // Part of every search response, though only the items are read so far
+    #[allow(dead_code)]
     total_count: usize,
+    #[allow(dead_code)]
     incomplete_results: bool,
     items: Vec<T>,
 }
@@ -0,0 +1,148 @@
+use serde::Deserialize;
+use serde_json::Value;
+use shared::plugin::{ActivityEventResponse, ActivityKind};
+
+#[derive(Deserialize)]
+pub struct EventRepoResponse {
+    /// Name including the owner, such as `octocat/hello-world`.
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct EventResponse {
+    pub r#type: String,
+    pub repo: EventRepoResponse,
+    pub public: bool,
+    /// Contents depend on the type of event.
+    pub payload: Value,
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+struct PushPayload {
+    r#ref: String,
+    #[serde(default)]
+    commits: Vec<Value>,
+    size: Option<usize>,
+    before: Option<String>,
+    head: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TitledItem {
+    title: String,
+    html_url: String,
+    #[serde(default)]
+    merged: bool,
+}
+
+#[derive(Deserialize)]
+struct PullRequestPayload {
+    action: String,
+    pull_request: TitledItem,
+}
+
+#[derive(Deserialize)]
+struct IssuesPayload {
+    action: String,
+    issue: TitledItem,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    name: Option<String>,
+    tag_name: String,
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct ReleasePayload {
+    action: String,
+    release: Release,
+}
+
+impl EventResponse {
+    /// Normalises events that can be shown as activity, ignoring any other kind of event.
+    pub fn into_activity(self) -> Option<ActivityEventResponse> {
+        let repo_url = format!("https://github.com/{}", self.repo.name);
+
+        let (kind, action, title, url) = match self.r#type.as_str() {
+            "PushEvent" => {
+                let push = serde_json::from_value::<PushPayload>(self.payload).ok()?;
+                let branch = push
+                    .r#ref
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(&push.r#ref)
+                    .to_string();
+                let commits = push.size.unwrap_or(push.commits.len());
+                let url = match (push.before, push.head) {
+                    (Some(before), Some(head)) => format!("{repo_url}/compare/{before}...{head}"),
+                    _ => repo_url,
+                };
+
+                (
+                    ActivityKind::Push,
+                    "pushed".to_string(),
+                    match commits {
+                        1 => format!("1 commit to {branch}"),
+                        commits => format!("{commits} commits to {branch}"),
+                    },
+                    url,
+                )
+            }
+            "PullRequestEvent" => {
+                let pull_request =
+                    serde_json::from_value::<PullRequestPayload>(self.payload).ok()?;
+                let action = match (
+                    pull_request.action.as_str(),
+                    pull_request.pull_request.merged,
+                ) {
+                    ("closed", true) => "merged".to_string(),
+                    _ => pull_request.action,
+                };
+
+                (
+                    ActivityKind::PullRequest,
+                    action,
+                    pull_request.pull_request.title,
+                    pull_request.pull_request.html_url,
+                )
+            }
+            "IssuesEvent" => {
+                let issues = serde_json::from_value::<IssuesPayload>(self.payload).ok()?;
+
+                (
+                    ActivityKind::Issue,
+                    issues.action,
+                    issues.issue.title,
+                    issues.issue.html_url,
+                )
+            }
+            "ReleaseEvent" => {
+                let release = serde_json::from_value::<ReleasePayload>(self.payload).ok()?;
+
+                (
+                    ActivityKind::Release,
+                    release.action,
+                    release
+                        .release
+                        .name
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or(release.release.tag_name),
+                    release.release.html_url,
+                )
+            }
+            _ => return None,
+        };
+
+        Some(ActivityEventResponse {
+            kind,
+            action,
+            repo: self.repo.name,
+            private: !self.public,
+            title,
+            url: Some(url),
+            created_at: self.created_at,
+        })
+    }
+}
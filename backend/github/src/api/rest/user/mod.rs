@@ -1,8 +1,10 @@
+mod event_response;
 mod user_response;
 
 use reqwest::{header, Client, Url};
 
 use crate::api::GithubApiError;
+pub use event_response::*;
 pub use user_response::*;
 
 pub struct UserApi {
@@ -33,4 +35,31 @@ impl UserApi {
             .await
             .map_err(GithubApiError::Response)
     }
+
+    /// Lists a user's most recent public events, newest first. The public endpoint is used as
+    /// the user is usually the one authenticated, who would otherwise see their private events.
+    pub async fn list_events(
+        &self,
+        access_token: &str,
+        username: &str,
+    ) -> Result<Vec<EventResponse>, GithubApiError> {
+        let mut url = self
+            .api_base
+            .join(&format!("users/{username}/events/public"))?;
+        url.query_pairs_mut().append_pair("per_page", "100");
+
+        let response = self
+            .client
+            .get(url)
+            .header(header::AUTHORIZATION, format!("Bearer {access_token}"))
+            .send()
+            .await?;
+
+        GithubApiError::match_status_code(response.status())?;
+
+        response
+            .json::<Vec<EventResponse>>()
+            .await
+            .map_err(GithubApiError::Response)
+    }
 }
@@ -97,7 +97,7 @@ struct OauthQueryParams {
 async fn handle_oauth(
     state: AuthState,
     params: Query<OauthQueryParams>,
-) -> Result<StatusCode, OAuthHandlerError> {
+) -> Result<String, OAuthHandlerError> {
     let access_token = state
         .oauth_api
         .get_access_token(&params.code)
@@ -106,16 +106,20 @@ async fn handle_oauth(
 
     let user_info = state.rest_api.user.get(&access_token).await?;
 
+    let payload = AuthTokenPayload::new(&state.source_identifier, &user_info.login, &access_token);
+    let preview_token = payload.preview_token.clone();
+    let settings_token = payload.settings_token.clone();
+
     state
         .save_auth_token
-        .send(AuthTokenPayload::new(
-            &state.source_identifier,
-            &user_info.login,
-            &access_token,
-        ))
+        .send(payload)
         .map_err(|_| OAuthHandlerError::Channel)?;
 
-    Ok(StatusCode::OK)
+    Ok(format!(
+        "Authenticated as {}. Send `Authorization: Bearer {preview_token}` to preview \
+         unpublished content, and `Authorization: Bearer {settings_token}` to change settings.",
+        user_info.login
+    ))
 }
 
 async fn handle_redirect(state: AuthState) -> Result<Redirect, OAuthHandlerError> {
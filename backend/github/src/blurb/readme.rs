@@ -1,10 +1,30 @@
 use std::sync::Arc;
 
 use axum::async_trait;
-use shared::plugin::{BlurbResponse, DataPlugin, PluginError, PluginIdentifier};
+use shared::plugin::{
+    BlurbFormat, BlurbResponse, DataPlugin, DataQuery, PluginError, PluginIdentifier,
+};
 
-use crate::api::rest::RestApi;
+use crate::{
+    api::rest::RestApi,
+    readme::{self, markdown},
+};
 
+/// Reads the format the blurb is returned in from the `format` parameter.
+fn format(query: &DataQuery) -> Result<BlurbFormat, PluginError> {
+    match query.get("format") {
+        None | Some("html") => Ok(BlurbFormat::Html),
+        Some("markdown") => Ok(BlurbFormat::Markdown),
+        Some("text") => Ok(BlurbFormat::Text),
+        Some(format) => Err(PluginError::InvalidParameter(
+            "format".to_string(),
+            format!("expected `html`, `markdown` or `text`, found `{format}`"),
+        )),
+    }
+}
+
+/// The user's profile README, or a section of it selected by the `section` parameter (a heading)
+/// or by `<!-- blurb:start -->` and `<!-- blurb:end -->` comments.
 pub struct BlurbReadme {
     rest_api: Arc<RestApi>,
 }
@@ -21,16 +41,49 @@ impl BlurbReadme {
 impl DataPlugin for BlurbReadme {
     type D = BlurbResponse;
 
-    async fn get_data(&self, username: &str, auth_token: &str) -> Result<Self::D, PluginError> {
-        Ok(self
-            .rest_api
-            .repositories
-            .get_readme(auth_token, username, username, true)
-            .await?
-            .into())
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let format = format(query)?;
+
+        // The profile README lives in a repository named after the user
+        let (repository, source) = futures::try_join!(
+            self.rest_api
+                .repositories
+                .get(auth_token, username, username),
+            self.rest_api
+                .repositories
+                .get_readme(auth_token, username, username, false),
+        )?;
+
+        let section =
+            markdown::section(&source, query.get("section")).ok_or(PluginError::NotFound)?;
+
+        let blurb = match format {
+            BlurbFormat::Html => {
+                let html = self
+                    .rest_api
+                    .markdown
+                    .render(auth_token, section, &repository.full_name)
+                    .await?;
+
+                readme::embeddable(&html, &repository)
+            }
+            BlurbFormat::Markdown => section.to_string(),
+            BlurbFormat::Text => markdown::plain_text(section),
+        };
+
+        Ok(BlurbResponse { blurb, format })
     }
 
     fn get_identifier(&self) -> PluginIdentifier {
         PluginIdentifier::new("readme")
     }
+
+    fn params(&self) -> &[&str] {
+        &["format", "section"]
+    }
 }
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::source::HealthCheck;
+
+use crate::api::rest::RestApi;
+
+pub struct GithubHealth {
+    rest_api: Arc<RestApi>,
+}
+
+impl GithubHealth {
+    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+        Self {
+            rest_api: rest_api.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for GithubHealth {
+    async fn check(&self) -> Result<(), String> {
+        self.rest_api
+            .rate_limit
+            .check()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
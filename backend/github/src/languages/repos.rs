@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::plugin::{
+    DataPlugin, DataQuery, LanguagesResponse, PluginError, PluginIdentifier,
+    RepositoryLanguagesResponse,
+};
+
+use crate::{api::rest::RestApi, projects::languages::LanguageCache};
+
+/// Languages across every repository the user has written, excluding forks.
+pub struct LanguagesRepos {
+    rest_api: Arc<RestApi>,
+    languages: Arc<LanguageCache>,
+}
+
+impl LanguagesRepos {
+    pub fn new(rest_api: &Arc<RestApi>, languages: &Arc<LanguageCache>) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+            languages: Arc::clone(languages),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for LanguagesRepos {
+    type D = LanguagesResponse;
+
+    async fn get_data(
+        &self,
+        _username: &str,
+        auth_token: &str,
+        _query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let repositories = self
+            .rest_api
+            .repositories
+            .list(auth_token)
+            .await?
+            .into_iter()
+            .filter(|repository| !repository.fork)
+            .collect::<Vec<_>>();
+
+        let languages = self.languages.get_all(auth_token, &repositories).await?;
+
+        Ok(LanguagesResponse::new(
+            repositories
+                .into_iter()
+                .zip(languages)
+                .map(|(repository, languages)| RepositoryLanguagesResponse {
+                    full_name: repository.full_name,
+                    private: repository.private,
+                    languages,
+                })
+                .collect(),
+        ))
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("repos")
+    }
+}
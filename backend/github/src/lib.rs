@@ -1,40 +1,67 @@
+mod activity;
 mod api;
 mod auth;
 mod blurb;
+mod health;
+mod languages;
 mod posts;
 mod projects;
+mod readme;
+mod releases;
+mod tags;
 mod user;
+mod webhook;
 
 use std::sync::Arc;
 
-use api::{oauth::OauthApi, rest::RestApi};
+use api::{graphql::GraphqlApi, oauth::OauthApi, rest::RestApi};
 use axum::http::{HeaderMap, HeaderValue};
-use projects::{repo_topics::RepoTags, repos::GithubProjectsRepos};
+use projects::{
+    detail::ProjectDetail, languages::LanguageCache, pinned::PinnedItems, repo_topics::RepoTags,
+    repos::GithubProjectsRepos,
+};
 use reqwest::{
     header::{self, InvalidHeaderValue},
     Client, Url,
 };
 
 use shared::{
-    environment::Environment,
-    get_from_environment,
-    plugin::{AuthPlugin, Plugin, SourceError, ToPlugin},
-    source::{Source, SourceIdentifier},
+    config::Section,
+    plugin::{AuthPlugin, Plugin, ToPlugin, WebhookPlugin},
+    source::{HealthCheck, Source, SourceIdentifier},
 };
 
 use auth::oauth::GithubOAuth;
-use user::GithubUserProfile;
+use health::GithubHealth;
+use user::{GithubUserProfile, ProfileCache};
+use webhook::events::GithubWebhook;
+
+use crate::{
+    activity::profile::ActivityProfile,
+    blurb::readme::BlurbReadme,
+    languages::repos::LanguagesRepos,
+    posts::{discussions::PostsDiscussions, files::PostsFiles, issues::PostsIssues},
+    releases::portfolio::ReleasesPortfolio,
+    tags::issues::TagsIssues,
+};
 
-use crate::{blurb::readme::BlurbReadme, posts::issues::PostsIssues};
+const DEFAULT_REST_BASE: &str = "https://api.github.com/";
+const DEFAULT_OAUTH_BASE: &str = "https://github.com/login/oauth/";
+const DEFAULT_POSTS_DIRECTORY: &str = "posts";
+const DEFAULT_DISCUSSIONS_CATEGORY: &str = "Blog";
 
 pub struct Github {
     rest_api: Arc<RestApi>,
+    graphql_api: Arc<GraphqlApi>,
+    languages: Arc<LanguageCache>,
+    profiles: Arc<ProfileCache>,
     oauth_api: Arc<OauthApi>,
+    webhook_secret: Option<String>,
+    posts_directory: String,
+    discussions_category: String,
 }
 impl Github {
-    pub fn from_environment(environment: &Environment) -> Result<Self, SourceError> {
-        let config = GithubConfig::from_environment(environment)?;
-
+    pub fn new(config: GithubConfig, user_agent: &str) -> Self {
         let client = Client::builder()
             .default_headers(
                 [(header::ACCEPT, "application/vnd.github+json")]
@@ -43,15 +70,14 @@ impl Github {
                     .collect::<Result<HeaderMap, InvalidHeaderValue>>()
                     .unwrap(),
             )
-            .user_agent(
-                environment
-                    .get("USER_AGENT")
-                    .ok_or(SourceError::MissingEnvVar("USER_AGENT".to_string()))?,
-            )
+            .user_agent(user_agent)
             .build()
             .unwrap();
 
         let rest_api = Arc::new(RestApi::new(&client, &config.rest_base));
+        let graphql_api = Arc::new(GraphqlApi::new(&client, &config.graphql_url));
+        let languages = Arc::new(LanguageCache::new(&rest_api));
+        let profiles = Arc::new(ProfileCache::new(&graphql_api, &languages));
         let oauth_api = Arc::new(OauthApi::new(
             &client,
             &config.oauth_base,
@@ -59,10 +85,16 @@ impl Github {
             &config.client_secret,
         ));
 
-        Ok(Self {
+        Self {
             rest_api,
+            graphql_api,
+            languages,
+            profiles,
             oauth_api,
-        })
+            webhook_secret: config.webhook_secret,
+            posts_directory: config.posts_directory,
+            discussions_category: config.discussions_category,
+        }
     }
 }
 
@@ -71,15 +103,54 @@ pub struct GithubConfig {
     client_secret: String,
     client_id: String,
     rest_base: Url,
+    /// GraphQL endpoint, defaulting to `graphql` relative to the REST base.
+    graphql_url: Url,
     oauth_base: Url,
+    /// Secret shared with GitHub to sign webhook deliveries. Webhooks are disabled without it.
+    webhook_secret: Option<String>,
+    /// Directory of the profile repository containing Markdown posts.
+    posts_directory: String,
+    /// Discussion category containing posts.
+    discussions_category: String,
 }
 impl GithubConfig {
-    pub fn from_environment(environment: &Environment) -> Result<Self, SourceError> {
-        Ok(Self {
-            client_secret: get_from_environment!(environment, "GITHUB_CLIENT_SECRET"),
-            client_id: get_from_environment!(environment, "GITHUB_CLIENT_ID"),
-            rest_base: get_from_environment!(environment, "GITHUB_REST_BASE").parse()?,
-            oauth_base: get_from_environment!(environment, "GITHUB_OAUTH_BASE").parse()?,
+    /// Reads the `github` section. Returns `None` if any value is missing or invalid, in which
+    /// case the problems are recorded in the configuration.
+    pub fn from_config(section: &Section) -> Option<Self> {
+        let client_secret = section.required("client_secret");
+        let client_id = section.required("client_id");
+        let rest_base = section
+            .optional("rest_base")
+            .unwrap_or_else(|| Url::parse(DEFAULT_REST_BASE).expect("default URL is valid"));
+        let graphql_url = section.optional("graphql_url");
+        let oauth_base = section
+            .optional("oauth_base")
+            .unwrap_or_else(|| Url::parse(DEFAULT_OAUTH_BASE).expect("default URL is valid"));
+        let webhook_secret = section.optional("webhook_secret");
+        let posts_directory = section
+            .optional("posts_directory")
+            .unwrap_or_else(|| DEFAULT_POSTS_DIRECTORY.to_string());
+        let discussions_category = section
+            .optional("discussions_category")
+            .unwrap_or_else(|| DEFAULT_DISCUSSIONS_CATEGORY.to_string());
+
+        let graphql_url = match graphql_url {
+            Some(url) => url,
+            None => rest_base
+                .join("graphql")
+                .map_err(|e| section.problem("rest_base", e.to_string()))
+                .ok()?,
+        };
+
+        Some(Self {
+            client_secret: client_secret?,
+            client_id: client_id?,
+            rest_base,
+            graphql_url,
+            oauth_base,
+            webhook_secret,
+            posts_directory,
+            discussions_category,
         })
     }
 }
@@ -91,15 +162,34 @@ impl Source for Github {
 
     fn get_plugins(&self) -> Vec<Plugin> {
         vec![
-            GithubUserProfile::new(&self.rest_api).to_plugin(),
-            GithubProjectsRepos::new(&self.rest_api).to_plugin(),
-            RepoTags::new(&self.rest_api).to_plugin(),
+            GithubUserProfile::new(&self.profiles).to_plugin(),
+            GithubProjectsRepos::new(&self.rest_api, &self.profiles, &self.languages).to_plugin(),
+            RepoTags::new(&self.rest_api, &self.languages).to_plugin(),
+            PinnedItems::new(&self.profiles).to_plugin(),
+            ProjectDetail::new(&self.rest_api, &self.graphql_api, &self.languages).to_plugin(),
             BlurbReadme::new(&self.rest_api).to_plugin(),
             PostsIssues::new(&self.rest_api).to_plugin(),
+            PostsFiles::new(&self.rest_api, &self.posts_directory).to_plugin(),
+            PostsDiscussions::new(&self.graphql_api, &self.discussions_category).to_plugin(),
+            TagsIssues::new(&self.rest_api).to_plugin(),
+            LanguagesRepos::new(&self.rest_api, &self.languages).to_plugin(),
+            ActivityProfile::new(&self.rest_api, &self.graphql_api).to_plugin(),
+            ReleasesPortfolio::new(&self.rest_api).to_plugin(),
         ]
     }
 
     fn get_auth_plugins(&self) -> Vec<Box<dyn AuthPlugin>> {
         vec![Box::new(GithubOAuth::new(&self.rest_api, &self.oauth_api)) as Box<dyn AuthPlugin>]
     }
+
+    fn get_webhook_plugins(&self) -> Vec<Box<dyn WebhookPlugin>> {
+        self.webhook_secret
+            .iter()
+            .map(|secret| Box::new(GithubWebhook::new(secret)) as Box<dyn WebhookPlugin>)
+            .collect()
+    }
+
+    fn get_health_check(&self) -> Box<dyn HealthCheck> {
+        Box::new(GithubHealth::new(&self.rest_api))
+    }
 }
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, PostsResponse};
+
+use crate::api::{graphql::GraphqlApi, GithubApiError};
+
+use super::{lifecycle, raw::RawPost};
+
+/// Posts written as discussions within a category of the user's profile repository.
+pub struct PostsDiscussions {
+    graphql_api: Arc<GraphqlApi>,
+    category: String,
+}
+
+impl PostsDiscussions {
+    pub fn new(graphql_api: &Arc<GraphqlApi>, category: &str) -> Self {
+        Self {
+            graphql_api: Arc::clone(graphql_api),
+            category: category.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for PostsDiscussions {
+    type D = PostsResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let discussions = match self
+            .graphql_api
+            .discussions
+            .list(auth_token, username, username)
+            .await
+        {
+            Ok(discussions) => discussions,
+            // A user without a profile repository has no posts
+            Err(GithubApiError::NotFound) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Anyone can start a discussion in a public repository, so only the user's own are posts
+        let mut posts = discussions
+            .into_iter()
+            .filter(|discussion| {
+                discussion
+                    .category
+                    .name
+                    .eq_ignore_ascii_case(&self.category)
+                    && discussion
+                        .author
+                        .as_ref()
+                        .is_some_and(|author| author.login.eq_ignore_ascii_case(username))
+            })
+            .map(|discussion| RawPost::from(discussion).into())
+            .collect();
+
+        lifecycle::filter_tags(&mut posts, query);
+
+        lifecycle::filter(posts, query)
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("discussions")
+    }
+
+    fn params(&self) -> &[&str] {
+        lifecycle::PARAMS
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use shared::plugin::{
+    DataPlugin, DataQuery, PluginError, PluginIdentifier, PostResponse, PostsResponse,
+};
+
+use crate::api::{
+    rest::{ContentResponse, RestApi},
+    GithubApiError,
+};
+
+use super::{lifecycle, raw::RawPost};
+
+/// Extension of files within the posts directory that are treated as posts.
+const POST_EXTENSION: &str = ".md";
+/// Maximum number of posts fetched at once for a single plugin run, each of which makes two
+/// requests.
+const CONCURRENCY: usize = 4;
+
+/// Posts written as Markdown files within a directory of the user's profile repository. Titles,
+/// tags and dates are taken from front matter, falling back to the file name and commit history.
+pub struct PostsFiles {
+    rest_api: Arc<RestApi>,
+    directory: String,
+}
+
+impl PostsFiles {
+    pub fn new(rest_api: &Arc<RestApi>, directory: &str) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+            directory: directory.trim_matches('/').to_string(),
+        }
+    }
+
+    async fn get_post(
+        &self,
+        username: &str,
+        auth_token: &str,
+        file: ContentResponse,
+    ) -> Result<PostResponse, GithubApiError> {
+        let repositories = &self.rest_api.repositories;
+        let (body, commits) = futures::try_join!(
+            repositories.get_file(auth_token, username, username, &file.path),
+            repositories.list_commits(auth_token, username, username, &file.path),
+        )?;
+
+        // Commits are listed newest first
+        let updated_at = commits
+            .first()
+            .and_then(|commit| commit.date())
+            .unwrap_or_default()
+            .to_string();
+        let created_at = commits
+            .last()
+            .and_then(|commit| commit.date())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(RawPost {
+            number: None,
+            title: title_from_file_name(&file.name),
+            body,
+            labels: Vec::new(),
+            category: None,
+            reactions: None,
+            created_at,
+            updated_at,
+            original_link: file.html_url,
+        }
+        .into())
+    }
+}
+
+/// Converts a file name such as `my-first-post.md` into a title such as `My first post`.
+fn title_from_file_name(name: &str) -> String {
+    let stem = name.strip_suffix(POST_EXTENSION).unwrap_or(name);
+    let mut title = stem.replace(['-', '_'], " ");
+    if let Some(first) = title.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+
+    title
+}
+
+#[async_trait]
+impl DataPlugin for PostsFiles {
+    type D = PostsResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let files = match self
+            .rest_api
+            .repositories
+            .list_contents(auth_token, username, username, &self.directory)
+            .await
+        {
+            Ok(files) => files,
+            // A user without a posts directory has no posts
+            Err(GithubApiError::NotFound) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Futures are created up front, as mapping the stream lazily isn't `Send`
+        let requests = files
+            .into_iter()
+            .filter(|file| file.r#type == "file" && file.name.ends_with(POST_EXTENSION))
+            .map(|file| self.get_post(username, auth_token, file))
+            .collect::<Vec<_>>();
+        let mut posts = stream::iter(requests)
+            .buffered(CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        lifecycle::filter_tags(&mut posts, query);
+
+        // Files have no inherent order, so list the most recently published first
+        posts.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+
+        lifecycle::filter(posts, query)
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("files")
+    }
+
+    fn params(&self) -> &[&str] {
+        lifecycle::PARAMS
+    }
+}
@@ -0,0 +1,204 @@
+use chrono::{DateTime, NaiveDate};
+use serde::Deserialize;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum FrontMatterError {
+    #[error("invalid YAML front matter: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("invalid TOML front matter: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("front matter is missing closing `{0}` delimiter")]
+    Unterminated(&'static str),
+}
+
+/// Fields that authors can set at the top of a post.
+#[derive(Debug, Default, Deserialize)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub slug: Option<String>,
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub canonical_url: Option<String>,
+    #[serde(alias = "publish_at")]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub unlisted: bool,
+}
+
+/// Front matter that has passed validation, along with any problems found. Invalid fields are
+/// dropped rather than failing the post.
+#[derive(Debug, Default)]
+pub struct ParsedFrontMatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub slug: Option<String>,
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub canonical_url: Option<String>,
+    pub published_at: Option<String>,
+    pub draft: bool,
+    pub unlisted: bool,
+    pub errors: Vec<String>,
+}
+
+enum Format {
+    Yaml,
+    Toml,
+}
+impl Format {
+    fn delimiter(&self) -> &'static str {
+        match self {
+            Format::Yaml => "---",
+            Format::Toml => "+++",
+        }
+    }
+}
+
+/// Splits optional front matter from the top of a post body, returning the parsed front matter
+/// and the remaining body.
+pub fn extract(body: &str) -> (ParsedFrontMatter, String) {
+    match split(body) {
+        Ok(Some((format, front_matter, body))) => {
+            let parsed = match format {
+                Format::Yaml => serde_yaml::from_str::<Option<FrontMatter>>(front_matter)
+                    .map(Option::unwrap_or_default)
+                    .map_err(FrontMatterError::from),
+                Format::Toml => toml::from_str::<toml::Table>(front_matter)
+                    .and_then(|table| stringify_dates(toml::Value::Table(table)).try_into())
+                    .map_err(FrontMatterError::from),
+            };
+
+            let parsed = match parsed {
+                Ok(front_matter) => validate(front_matter),
+                Err(e) => ParsedFrontMatter {
+                    errors: vec![e.to_string()],
+                    ..Default::default()
+                },
+            };
+
+            (parsed, body.to_string())
+        }
+        Ok(None) => (ParsedFrontMatter::default(), body.to_string()),
+        Err(e) => (
+            ParsedFrontMatter {
+                errors: vec![e.to_string()],
+                ..Default::default()
+            },
+            body.to_string(),
+        ),
+    }
+}
+
+fn split(body: &str) -> Result<Option<(Format, &str, &str)>, FrontMatterError> {
+    let (first_line, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let format = match first_line.trim_end() {
+        "---" => Format::Yaml,
+        "+++" => Format::Toml,
+        _ => return Ok(None),
+    };
+
+    // Find the closing delimiter, tracking the byte offset of each line
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == format.delimiter() {
+            let front_matter = &rest[..offset];
+            let body = rest[offset + line.len()..].trim_start_matches(['\r', '\n']);
+
+            return Ok(Some((format, front_matter, body)));
+        }
+
+        offset += line.len();
+    }
+
+    Err(FrontMatterError::Unterminated(format.delimiter()))
+}
+
+/// TOML has a native date type, which is converted to a string to match the YAML representation.
+fn stringify_dates(value: toml::Value) -> toml::Value {
+    match value {
+        toml::Value::Datetime(datetime) => toml::Value::String(datetime.to_string()),
+        toml::Value::Array(array) => {
+            toml::Value::Array(array.into_iter().map(stringify_dates).collect())
+        }
+        toml::Value::Table(table) => toml::Value::Table(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, stringify_dates(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn validate(front_matter: FrontMatter) -> ParsedFrontMatter {
+    let mut errors = Vec::new();
+
+    let mut check = |field: &str, value: Option<String>, valid: fn(&str) -> Option<String>| {
+        value.and_then(|value| {
+            let normalised = valid(&value);
+            if normalised.is_none() {
+                errors.push(format!("invalid `{field}`: {value}"));
+            }
+
+            normalised
+        })
+    };
+
+    let slug = check("slug", front_matter.slug, |slug| {
+        (!slug.is_empty()
+            && slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'))
+        .then(|| slug.to_string())
+    });
+    let cover_image = check("cover_image", front_matter.cover_image, parse_url);
+    let canonical_url = check("canonical_url", front_matter.canonical_url, parse_url);
+    let published_at = check("date", front_matter.date, parse_date);
+
+    ParsedFrontMatter {
+        title: front_matter.title,
+        tags: front_matter.tags,
+        slug,
+        summary: front_matter.summary,
+        cover_image,
+        canonical_url,
+        published_at,
+        draft: front_matter.draft,
+        unlisted: front_matter.unlisted,
+        errors,
+    }
+}
+
+fn parse_url(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(String::from)
+}
+
+/// Accepts either a full RFC 3339 timestamp or a plain date, normalising to RFC 3339.
+pub fn parse_date(date: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.to_rfc3339())
+        .or_else(|_| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc().to_rfc3339())
+        })
+        .ok()
+}
+
+/// Generates a URL safe slug from a post title.
+pub fn slugify(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -1,9 +1,40 @@
 use std::sync::Arc;
 
 use axum::async_trait;
-use shared::plugin::{DataPlugin, PluginError, PluginIdentifier, PostsResponse};
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, PostsResponse};
 
-use crate::api::rest::RestApi;
+use crate::api::{
+    rest::{IssueResponse, RestApi},
+    GithubApiError,
+};
+
+use super::lifecycle;
+
+/// Label marking an issue in the user's profile repository as a post.
+pub const POST_LABEL: &str = "post";
+
+/// Searches for posts in the user's profile repository, restricted to those with every one of the
+/// given tags.
+pub async fn search_posts(
+    rest_api: &RestApi,
+    username: &str,
+    auth_token: &str,
+    tags: &[&str],
+) -> Result<Vec<IssueResponse>, GithubApiError> {
+    tags.iter()
+        .fold(
+            rest_api
+                .search
+                .issues
+                .builder(auth_token)
+                .open()
+                .repo(&format!("{username}/{username}"))
+                .label(POST_LABEL),
+            |builder, tag| builder.label(tag),
+        )
+        .search()
+        .await
+}
 
 pub struct PostsIssues {
     rest_api: Arc<RestApi>,
@@ -21,23 +52,61 @@ impl PostsIssues {
 impl DataPlugin for PostsIssues {
     type D = PostsResponse;
 
-    async fn get_data(&self, username: &str, auth_token: &str) -> Result<Self::D, PluginError> {
-        Ok(self
-            .rest_api
-            .search
-            .issues
-            .builder(auth_token)
-            .open()
-            .repo(&format!("{username}/{username}"))
-            .label("post")
-            .search()
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        // Multiple tags can be provided, separated by commas
+        let tags = query
+            .get("tag")
+            .map(|tags| tags.split(',').collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        if !tags.is_empty() {
+            if let Some(tag) = tags
+                .iter()
+                .find(|tag| tag.contains('"') || tag.contains(char::is_control))
+            {
+                return Err(PluginError::InvalidParameter(
+                    "tag".to_string(),
+                    format!(
+                        "`{}` contains a quote or control character",
+                        tag.escape_debug()
+                    ),
+                ));
+            }
+
+            // A tag that isn't a label in the repository can't match any posts
+            let labels = self
+                .rest_api
+                .repositories
+                .list_labels(auth_token, username, username)
+                .await?;
+            if !tags.iter().all(|tag| {
+                labels
+                    .iter()
+                    .any(|label| label.name.eq_ignore_ascii_case(tag))
+            }) {
+                return Ok(Vec::new());
+            }
+        }
+
+        let posts = search_posts(&self.rest_api, username, auth_token, &tags)
             .await?
             .into_iter()
             .map(|issue| issue.into())
-            .collect())
+            .collect();
+
+        lifecycle::filter(posts, query)
     }
 
     fn get_identifier(&self) -> PluginIdentifier {
         PluginIdentifier::new("issues")
     }
+
+    fn params(&self) -> &[&str] {
+        lifecycle::PARAMS
+    }
 }
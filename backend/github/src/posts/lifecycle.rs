@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use shared::plugin::{DataQuery, PluginError, PostResponse, PostStatus};
+
+/// Label marking a post as a draft.
+pub const DRAFT_LABEL: &str = "draft";
+/// Label marking a post as unlisted.
+pub const UNLISTED_LABEL: &str = "unlisted";
+
+/// Parameters read by posts plugins: `slug` by [`filter`], and `tag` to only include posts with
+/// the given tags.
+pub const PARAMS: &[&str] = &["slug", "tag"];
+
+pub fn is_lifecycle_label(label: &str) -> bool {
+    label == DRAFT_LABEL || label == UNLISTED_LABEL
+}
+
+/// Determines the status of a post. Drafts take priority over scheduling, which takes priority
+/// over being unlisted.
+pub fn status(draft: bool, unlisted: bool, published_at: &str) -> PostStatus {
+    let scheduled = DateTime::parse_from_rfc3339(published_at)
+        .map(|published_at| published_at > Utc::now())
+        .unwrap_or(false);
+
+    if draft {
+        PostStatus::Draft
+    } else if scheduled {
+        PostStatus::Scheduled
+    } else if unlisted {
+        PostStatus::Unlisted
+    } else {
+        PostStatus::Published
+    }
+}
+
+/// Whether a post should be included in listings for the request.
+pub fn is_listed(post: &PostResponse, query: &DataQuery) -> bool {
+    query.preview() || post.status == PostStatus::Published
+}
+
+/// Filters posts according to the request. Listings only include published posts, whilst a
+/// `slug` parameter selects a single published or unlisted post. Previews include every post.
+pub fn filter(
+    posts: Vec<PostResponse>,
+    query: &DataQuery,
+) -> Result<Vec<PostResponse>, PluginError> {
+    // The output changes once the next scheduled post is published
+    for post in posts
+        .iter()
+        .filter(|post| post.status == PostStatus::Scheduled)
+    {
+        if let Ok(published_at) = DateTime::parse_from_rfc3339(&post.published_at) {
+            query.expires_at(published_at.into());
+        }
+    }
+
+    let Some(slug) = query.get("slug") else {
+        return Ok(posts
+            .into_iter()
+            .filter(|post| is_listed(post, query))
+            .collect());
+    };
+
+    posts
+        .into_iter()
+        .find(|post| {
+            post.slug == slug && (is_listed(post, query) || post.status == PostStatus::Unlisted)
+        })
+        .map(|post| vec![post])
+        .ok_or(PluginError::NotFound)
+}
+
+/// Removes posts without every tag given by a `tag` parameter, for sources that can't filter by
+/// tag themselves. Multiple tags can be provided, separated by commas. Tags are matched ignoring
+/// case, as GitHub does when filtering issues by label.
+pub fn filter_tags(posts: &mut Vec<PostResponse>, query: &DataQuery) {
+    if let Some(tags) = query.get("tag") {
+        let tags = tags.split(',').map(str::to_lowercase).collect::<Vec<_>>();
+        posts.retain(|post| {
+            tags.iter()
+                .all(|tag| post.tags.iter().any(|t| t.to_lowercase() == *tag))
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use shared::plugin::TableOfContentsEntry;
+
+/// Maximum length of a generated excerpt, in characters.
+const EXCERPT_LENGTH: usize = 200;
+/// Average reading speed used to estimate reading time.
+const WORDS_PER_MINUTE: usize = 200;
+
+pub struct PostMetadata {
+    pub excerpt: String,
+    pub word_count: usize,
+    pub reading_time: usize,
+    pub table_of_contents: Vec<TableOfContentsEntry>,
+}
+
+/// Derives metadata from the Markdown body of a post. Code blocks are excluded from the word
+/// count and excerpt.
+pub fn derive(body: &str) -> PostMetadata {
+    let mut paragraphs = String::new();
+    let mut word_count = 0;
+    let mut table_of_contents = Vec::new();
+    let mut slugger = Slugger::default();
+
+    // Heading currently being collected, along with its text
+    let mut heading = None;
+    let mut in_code_block = false;
+    let mut in_paragraph = false;
+
+    for event in Parser::new_ext(body, Options::all()) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => heading = Some((level, String::new())),
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, title)) = heading.take() {
+                    table_of_contents.push(TableOfContentsEntry {
+                        level: level as u8,
+                        anchor: slugger.slug(&title),
+                        title,
+                    });
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(TagEnd::Paragraph) => {
+                in_paragraph = false;
+                paragraphs.push(' ');
+            }
+            Event::SoftBreak | Event::HardBreak if in_paragraph => paragraphs.push(' '),
+            Event::Text(text) | Event::Code(text) if !in_code_block => {
+                word_count += text.split_whitespace().count();
+
+                if let Some((_, title)) = heading.as_mut() {
+                    title.push_str(&text);
+                } else if in_paragraph {
+                    paragraphs.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    PostMetadata {
+        excerpt: truncate(&paragraphs),
+        word_count,
+        reading_time: word_count.div_ceil(WORDS_PER_MINUTE).max(1),
+        table_of_contents,
+    }
+}
+
+/// Truncates text to the excerpt length on a word boundary.
+fn truncate(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= EXCERPT_LENGTH {
+        return text;
+    }
+
+    let mut excerpt = String::new();
+    for word in text.split(' ') {
+        if excerpt.chars().count() + word.chars().count() + 1 > EXCERPT_LENGTH {
+            break;
+        }
+
+        if !excerpt.is_empty() {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+    }
+    excerpt.push('…');
+
+    excerpt
+}
+
+/// Generates heading anchors the same way GitHub does when rendering Markdown, including
+/// suffixing duplicate headings.
+#[derive(Default)]
+struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    fn slug(&mut self, heading: &str) -> String {
+        let slug = heading
+            .trim()
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+                _ => None,
+            })
+            .collect::<String>();
+
+        let occurrences = self.occurrences.entry(slug.clone()).or_default();
+        let slug = match *occurrences {
+            0 => slug,
+            n => format!("{slug}-{n}"),
+        };
+        *occurrences += 1;
+
+        slug
+    }
+}
@@ -1 +1,7 @@
+pub mod discussions;
+pub mod files;
+pub mod front_matter;
 pub mod issues;
+pub mod lifecycle;
+pub mod metadata;
+pub mod raw;
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use shared::plugin::PostResponse;
+
+use super::{
+    front_matter::{self, slugify},
+    lifecycle::{self, is_lifecycle_label, DRAFT_LABEL, UNLISTED_LABEL},
+    metadata,
+};
+
+/// A post as provided by a source, before front matter is applied and metadata derived.
+pub struct RawPost {
+    pub number: Option<usize>,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub category: Option<String>,
+    pub reactions: Option<BTreeMap<String, usize>>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub original_link: String,
+}
+
+impl From<RawPost> for PostResponse {
+    fn from(post: RawPost) -> Self {
+        let (front_matter, body) = front_matter::extract(&post.body);
+        let metadata = metadata::derive(&body);
+
+        let has_label = |name: &str| post.labels.iter().any(|label| label == name);
+        let published_at = front_matter
+            .published_at
+            .unwrap_or_else(|| post.created_at.clone());
+        let status = lifecycle::status(
+            front_matter.draft || has_label(DRAFT_LABEL),
+            front_matter.unlisted || has_label(UNLISTED_LABEL),
+            &published_at,
+        );
+
+        let title = front_matter.title.unwrap_or(post.title);
+        let mut tags = post
+            .labels
+            .into_iter()
+            .filter(|label| !is_lifecycle_label(label))
+            .collect::<Vec<_>>();
+        for tag in front_matter.tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        PostResponse {
+            number: post.number,
+            slug: front_matter.slug.unwrap_or_else(|| slugify(&title)),
+            status,
+            title,
+            summary: front_matter.summary,
+            cover_image: front_matter.cover_image,
+            canonical_url: front_matter.canonical_url,
+            excerpt: Some(metadata.excerpt),
+            word_count: Some(metadata.word_count),
+            reading_time: Some(metadata.reading_time),
+            table_of_contents: Some(metadata.table_of_contents),
+            body,
+            tags,
+            category: post.category,
+            reactions: post.reactions,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            published_at,
+            original_link: post.original_link,
+            errors: front_matter.errors,
+        }
+    }
+}
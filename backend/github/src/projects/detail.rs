@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, ProjectDetailResponse};
+
+use crate::{
+    api::{graphql::GraphqlApi, rest::RestApi, GithubApiError},
+    readme,
+    releases::portfolio::embed_latest_releases,
+};
+
+use super::languages::LanguageCache;
+
+/// Whether a name can be a repository name, so that it can be placed in request paths. Names
+/// only contain ASCII letters, digits, `.`, `-` and `_`, and can't be `.` or `..`.
+fn is_repository_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// A single one of the user's repositories, selected by the `repo` parameter, along with its
+/// README and statistics.
+pub struct ProjectDetail {
+    rest_api: Arc<RestApi>,
+    graphql_api: Arc<GraphqlApi>,
+    languages: Arc<LanguageCache>,
+}
+
+impl ProjectDetail {
+    pub fn new(
+        rest_api: &Arc<RestApi>,
+        graphql_api: &Arc<GraphqlApi>,
+        languages: &Arc<LanguageCache>,
+    ) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+            graphql_api: Arc::clone(graphql_api),
+            languages: Arc::clone(languages),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for ProjectDetail {
+    type D = ProjectDetailResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let repo = query
+            .get("repo")
+            .ok_or_else(|| PluginError::MissingParameter("repo".to_string()))?;
+        if !is_repository_name(repo) {
+            return Err(PluginError::InvalidParameter(
+                "repo".to_string(),
+                format!("`{}` is not a repository name", repo.escape_debug()),
+            ));
+        }
+
+        let (repository, readme, open_pull_requests) = futures::try_join!(
+            self.rest_api.repositories.get(auth_token, username, repo),
+            async {
+                // A repository without a README is still shown
+                match self
+                    .rest_api
+                    .repositories
+                    .get_readme(auth_token, username, repo, true)
+                    .await
+                {
+                    Ok(readme) => Ok(Some(readme)),
+                    Err(GithubApiError::NotFound) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            },
+            self.graphql_api
+                .pull_requests
+                .count_open(auth_token, username, repo),
+        )?;
+
+        let repositories = [repository];
+        let mut projects = self.languages.projects(auth_token, &repositories).await?;
+        embed_latest_releases(
+            &self.rest_api,
+            auth_token,
+            &repositories,
+            &mut projects,
+            query,
+        )
+        .await?;
+
+        let [repository] = repositories;
+        let readme = readme.map(|readme| readme::embeddable(&readme, &repository));
+        let project = projects.pop().ok_or(PluginError::Internal)?;
+
+        Ok(ProjectDetailResponse {
+            topics: repository.topics,
+            project,
+            readme,
+            license: repository
+                .license
+                .map(|license| license.spdx_id.unwrap_or(license.name)),
+            created_at: repository.created_at,
+            pushed_at: repository.pushed_at,
+            default_branch: repository.default_branch,
+            open_pull_requests: Some(open_pull_requests),
+        })
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("repo")
+    }
+
+    fn required_params(&self) -> &[&str] {
+        &["repo"]
+    }
+
+    fn params(&self) -> &[&str] {
+        &["repo", "release"]
+    }
+}
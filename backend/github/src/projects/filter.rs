@@ -0,0 +1,186 @@
+use std::cmp::Ordering;
+
+use shared::plugin::{DataQuery, PluginError};
+
+use crate::api::rest::RepositoryResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Stars,
+    Pushed,
+    Created,
+    Name,
+}
+
+/// Filters and sorting for repositories, read from request parameters:
+///
+/// - `exclude`: comma separated list of `forks`, `archived` and `private`
+/// - `owned`: `true` to only include repositories owned by the user
+/// - `min_stars`: minimum number of stars
+/// - `topic` / `exclude_topic`: comma separated topics that must all be present / absent
+/// - `language`: primary language, ignoring case
+/// - `sort`: one of `stars`, `pushed`, `created` or `name`, with `order` of `asc` or `desc`
+#[derive(Debug, Default)]
+pub struct ProjectFilter {
+    exclude_forks: bool,
+    exclude_archived: bool,
+    exclude_private: bool,
+    owned: bool,
+    min_stars: usize,
+    topics: Vec<String>,
+    exclude_topics: Vec<String>,
+    language: Option<String>,
+    sort: Option<(SortKey, bool)>,
+}
+
+fn invalid(parameter: &str, message: impl Into<String>) -> PluginError {
+    PluginError::InvalidParameter(parameter.to_string(), message.into())
+}
+
+fn list(query: &DataQuery, parameter: &str) -> Vec<String> {
+    query
+        .get(parameter)
+        .map(|values| {
+            values
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl ProjectFilter {
+    pub fn from_query(query: &DataQuery) -> Result<Self, PluginError> {
+        let mut filter = Self::default();
+
+        for exclude in list(query, "exclude") {
+            match exclude.as_str() {
+                "forks" => filter.exclude_forks = true,
+                "archived" => filter.exclude_archived = true,
+                "private" => filter.exclude_private = true,
+                other => {
+                    return Err(invalid(
+                        "exclude",
+                        format!("expected `forks`, `archived` or `private`, found `{other}`"),
+                    ))
+                }
+            }
+        }
+
+        filter.owned = match query.get("owned") {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(other) => {
+                return Err(invalid(
+                    "owned",
+                    format!("expected `true` or `false`, found `{other}`"),
+                ))
+            }
+        };
+
+        if let Some(min_stars) = query.get("min_stars") {
+            filter.min_stars = min_stars.parse().map_err(|_| {
+                invalid(
+                    "min_stars",
+                    format!("expected a number, found `{min_stars}`"),
+                )
+            })?;
+        }
+
+        filter.topics = list(query, "topic");
+        filter.exclude_topics = list(query, "exclude_topic");
+        filter.language = query.get("language").map(str::to_lowercase);
+
+        if let Some(sort) = query.get("sort") {
+            let key = match sort {
+                "stars" => SortKey::Stars,
+                "pushed" => SortKey::Pushed,
+                "created" => SortKey::Created,
+                "name" => SortKey::Name,
+                other => {
+                    return Err(invalid(
+                        "sort",
+                        format!("expected `stars`, `pushed`, `created` or `name`, found `{other}`"),
+                    ))
+                }
+            };
+            // Names are sorted alphabetically by default, and everything else largest first
+            let descending = match query.get("order") {
+                None => key != SortKey::Name,
+                Some("asc") => false,
+                Some("desc") => true,
+                Some(other) => {
+                    return Err(invalid(
+                        "order",
+                        format!("expected `asc` or `desc`, found `{other}`"),
+                    ))
+                }
+            };
+
+            filter.sort = Some((key, descending));
+        }
+
+        Ok(filter)
+    }
+
+    fn matches(&self, username: &str, repository: &RepositoryResponse) -> bool {
+        let excluded = (self.exclude_forks && repository.fork)
+            || (self.exclude_archived && repository.archived)
+            || (self.exclude_private && repository.private)
+            || (self.owned && !repository.owner.login.eq_ignore_ascii_case(username));
+        if excluded || repository.stargazers_count < self.min_stars {
+            return false;
+        }
+
+        let topics = self
+            .topics
+            .iter()
+            .all(|topic| repository.topics.contains(topic))
+            && !self
+                .exclude_topics
+                .iter()
+                .any(|topic| repository.topics.contains(topic));
+        let language = self.language.as_ref().is_none_or(|language| {
+            repository
+                .language
+                .as_ref()
+                .is_some_and(|repository_language| repository_language.to_lowercase() == *language)
+        });
+
+        topics && language
+    }
+
+    fn compare(key: SortKey, a: &RepositoryResponse, b: &RepositoryResponse) -> Ordering {
+        match key {
+            SortKey::Stars => a.stargazers_count.cmp(&b.stargazers_count),
+            // Timestamps are ISO 8601, so compare correctly as strings
+            SortKey::Pushed => a.pushed_at.cmp(&b.pushed_at),
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        }
+    }
+
+    /// Removes repositories that don't match, then sorts those remaining if requested.
+    pub fn apply(
+        &self,
+        username: &str,
+        mut repositories: Vec<RepositoryResponse>,
+    ) -> Vec<RepositoryResponse> {
+        repositories.retain(|repository| self.matches(username, repository));
+
+        if let Some((key, descending)) = self.sort {
+            repositories.sort_by(|a, b| {
+                let ordering = Self::compare(key, a, b);
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        repositories
+    }
+}
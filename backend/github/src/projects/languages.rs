@@ -0,0 +1,109 @@
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use shared::plugin::{LanguageResponse, ProjectResponse};
+use tokio::sync::RwLock;
+
+use crate::api::{
+    rest::{RepositoryResponse, RestApi},
+    GithubApiError,
+};
+
+/// Maximum number of languages requests in flight for a single plugin run.
+const CONCURRENCY: usize = 8;
+
+struct CachedLanguages {
+    /// When the repository was last pushed to, as languages only change on push.
+    pushed_at: Option<String>,
+    languages: Vec<LanguageResponse>,
+}
+
+/// Fetches the language breakdown of repositories, keeping each until the repository is pushed
+/// to again.
+pub struct LanguageCache {
+    rest_api: Arc<RestApi>,
+    entries: RwLock<HashMap<String, CachedLanguages>>,
+}
+
+impl LanguageCache {
+    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get(
+        &self,
+        access_token: &str,
+        repository: &RepositoryResponse,
+    ) -> Result<Vec<LanguageResponse>, GithubApiError> {
+        if let Some(entry) = self.entries.read().await.get(&repository.full_name) {
+            if entry.pushed_at == repository.pushed_at {
+                return Ok(entry.languages.clone());
+            }
+        }
+
+        let languages = LanguageResponse::from_bytes(
+            self.rest_api
+                .repositories
+                .list_languages(access_token, &repository.full_name)
+                .await?,
+        );
+
+        self.entries.write().await.insert(
+            repository.full_name.clone(),
+            CachedLanguages {
+                pushed_at: repository.pushed_at.clone(),
+                languages: languages.clone(),
+            },
+        );
+
+        Ok(languages)
+    }
+
+    /// Stores a language breakdown fetched by other means, such as along with the repository.
+    pub async fn insert(&self, repository: &RepositoryResponse, languages: &[LanguageResponse]) {
+        self.entries.write().await.insert(
+            repository.full_name.clone(),
+            CachedLanguages {
+                pushed_at: repository.pushed_at.clone(),
+                languages: languages.to_vec(),
+            },
+        );
+    }
+
+    /// Gets the language breakdown of several repositories concurrently, in the same order.
+    pub async fn get_all(
+        &self,
+        access_token: &str,
+        repositories: &[RepositoryResponse],
+    ) -> Result<Vec<Vec<LanguageResponse>>, GithubApiError> {
+        // Futures are created up front, as mapping the stream lazily isn't `Send`
+        let requests = repositories
+            .iter()
+            .map(|repository| self.get(access_token, repository))
+            .collect::<Vec<_>>();
+
+        stream::iter(requests)
+            .buffered(CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Converts repositories into projects, including their language breakdown.
+    pub async fn projects(
+        &self,
+        access_token: &str,
+        repositories: &[RepositoryResponse],
+    ) -> Result<Vec<ProjectResponse>, GithubApiError> {
+        Ok(repositories
+            .iter()
+            .zip(self.get_all(access_token, repositories).await?)
+            .map(|(repository, languages)| ProjectResponse {
+                languages: Some(languages),
+                ..repository.into()
+            })
+            .collect())
+    }
+}
@@ -1,2 +1,20 @@
+pub mod detail;
+pub mod filter;
+pub mod languages;
+pub mod pinned;
 pub mod repo_topics;
 pub mod repos;
+
+/// Parameters read by the plugins listing projects: those of [`filter::ProjectFilter`], and
+/// `release` to embed each project's latest release.
+pub const LIST_PARAMS: &[&str] = &[
+    "exclude",
+    "owned",
+    "min_stars",
+    "topic",
+    "exclude_topic",
+    "language",
+    "sort",
+    "order",
+    "release",
+];
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, ProjectsResponse};
+
+use crate::user::ProfileCache;
+
+/// Repositories and gists pinned to the user's profile, in the order they are pinned.
+pub struct PinnedItems {
+    profiles: Arc<ProfileCache>,
+}
+
+impl PinnedItems {
+    pub fn new(profiles: &Arc<ProfileCache>) -> Self {
+        Self {
+            profiles: Arc::clone(profiles),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for PinnedItems {
+    type D = ProjectsResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        _query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        Ok(self
+            .profiles
+            .get(username, auth_token)
+            .await?
+            .pinned_items
+            .iter()
+            .cloned()
+            .map(|item| item.into())
+            .collect())
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("pinned")
+    }
+}
@@ -1,20 +1,25 @@
 use std::sync::Arc;
 
 use axum::async_trait;
-use shared::plugin::{
-    DataPlugin, PluginError, PluginIdentifier, ProjectResponse, ProjectsResponse,
-};
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, ProjectsResponse};
 
-use crate::api::rest::RestApi;
+use crate::{api::rest::RestApi, releases::portfolio::embed_latest_releases};
+
+use super::{filter::ProjectFilter, languages::LanguageCache};
+
+/// Topic marking a repository as part of the user's portfolio.
+pub const PORTFOLIO_TOPIC: &str = "portfolio";
 
 pub struct RepoTags {
     rest_api: Arc<RestApi>,
+    languages: Arc<LanguageCache>,
 }
 
 impl RepoTags {
-    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+    pub fn new(rest_api: &Arc<RestApi>, languages: &Arc<LanguageCache>) -> Self {
         Self {
             rest_api: Arc::clone(rest_api),
+            languages: Arc::clone(languages),
         }
     }
 }
@@ -23,19 +28,40 @@ impl RepoTags {
 impl DataPlugin for RepoTags {
     type D = ProjectsResponse;
 
-    async fn get_data(&self, _username: &str, auth_token: &str) -> Result<Self::D, PluginError> {
-        Ok(self
-            .rest_api
-            .search
-            .repositories
-            .by_topics(auth_token, &["portfolio".to_string()])
-            .await?
-            .iter()
-            .map(ProjectResponse::from)
-            .collect())
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let filter = ProjectFilter::from_query(query)?;
+        let repositories = filter.apply(
+            username,
+            self.rest_api
+                .search
+                .repositories
+                .by_topics(auth_token, &[PORTFOLIO_TOPIC.to_string()])
+                .await?,
+        );
+
+        let mut projects = self.languages.projects(auth_token, &repositories).await?;
+        embed_latest_releases(
+            &self.rest_api,
+            auth_token,
+            &repositories,
+            &mut projects,
+            query,
+        )
+        .await?;
+
+        Ok(projects)
     }
 
     fn get_identifier(&self) -> PluginIdentifier {
         PluginIdentifier::new("repo_topics")
     }
+
+    fn params(&self) -> &[&str] {
+        super::LIST_PARAMS
+    }
 }
@@ -1,17 +1,27 @@
 use std::sync::Arc;
 
 use axum::async_trait;
-use shared::plugin::{DataPlugin, PluginError, PluginIdentifier, ProjectsResponse};
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, ProjectsResponse};
 
-use crate::api::rest::RestApi;
+use crate::{api::rest::RestApi, releases::portfolio::embed_latest_releases, user::ProfileCache};
+
+use super::{filter::ProjectFilter, languages::LanguageCache};
 
 pub struct GithubProjectsRepos {
     rest_api: Arc<RestApi>,
+    profiles: Arc<ProfileCache>,
+    languages: Arc<LanguageCache>,
 }
 impl GithubProjectsRepos {
-    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+    pub fn new(
+        rest_api: &Arc<RestApi>,
+        profiles: &Arc<ProfileCache>,
+        languages: &Arc<LanguageCache>,
+    ) -> Self {
         Self {
             rest_api: Arc::clone(rest_api),
+            profiles: Arc::clone(profiles),
+            languages: Arc::clone(languages),
         }
     }
 }
@@ -20,18 +30,44 @@ impl GithubProjectsRepos {
 impl DataPlugin for GithubProjectsRepos {
     type D = ProjectsResponse;
 
-    async fn get_data(&self, _username: &str, auth_token: &str) -> Result<Self::D, PluginError> {
-        Ok(self
-            .rest_api
-            .repositories
-            .list(auth_token)
-            .await?
-            .iter()
-            .map(|repo| repo.into())
-            .collect())
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let filter = ProjectFilter::from_query(query)?;
+        let repositories = filter.apply(
+            username,
+            self.profiles
+                .get(username, auth_token)
+                .await?
+                .repositories
+                .iter()
+                .map(|repository| repository.repository.clone())
+                .collect(),
+        );
+
+        // Languages were stored when the profile was fetched, so aren't requested again
+
+        let mut projects = self.languages.projects(auth_token, &repositories).await?;
+        embed_latest_releases(
+            &self.rest_api,
+            auth_token,
+            &repositories,
+            &mut projects,
+            query,
+        )
+        .await?;
+
+        Ok(projects)
     }
 
     fn get_identifier(&self) -> PluginIdentifier {
         PluginIdentifier::new("repos")
     }
+
+    fn params(&self) -> &[&str] {
+        super::LIST_PARAMS
+    }
 }
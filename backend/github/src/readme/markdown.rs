@@ -0,0 +1,96 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+const START_MARKER: &str = "<!-- blurb:start -->";
+const END_MARKER: &str = "<!-- blurb:end -->";
+
+/// Normalises a heading for comparison, ignoring case, punctuation and emoji.
+fn normalise(heading: &str) -> String {
+    heading
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Finds the content between blurb marker comments. A missing end marker extends the section to
+/// the end of the document.
+fn between_markers(markdown: &str) -> Option<&str> {
+    let start = markdown.find(START_MARKER)? + START_MARKER.len();
+    let end = markdown[start..]
+        .find(END_MARKER)
+        .map_or(markdown.len(), |end| start + end);
+
+    Some(&markdown[start..end])
+}
+
+/// Finds the content under a heading, up to the next heading of the same or a higher level.
+fn under_heading<'m>(markdown: &'m str, name: &str) -> Option<&'m str> {
+    let name = normalise(name);
+
+    // Heading currently being collected, along with its text
+    let mut heading = None;
+    // Level of the matching heading, and where its content starts
+    let mut section = None;
+
+    for (event, range) in Parser::new_ext(markdown, Options::all()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                if let Some((section_level, start)) = section {
+                    if level <= section_level {
+                        return Some(&markdown[start..range.start]);
+                    }
+                }
+                heading = Some((level, String::new()));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, title)) = heading.take() {
+                    if section.is_none() && normalise(&title) == name {
+                        section = Some((level, range.end));
+                    }
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, title)) = heading.as_mut() {
+                    title.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    section.map(|(_, start)| &markdown[start..])
+}
+
+/// Selects the part of a README used as a blurb: the content under the named heading if one is
+/// given, otherwise the content between marker comments, otherwise the whole README.
+pub fn section<'m>(markdown: &'m str, heading: Option<&str>) -> Option<&'m str> {
+    match heading {
+        Some(heading) => under_heading(markdown, heading),
+        None => Some(between_markers(markdown).unwrap_or(markdown)),
+    }
+    .map(str::trim)
+}
+
+/// Converts Markdown to plain text, keeping paragraphs and list items on separate lines. HTML
+/// such as badges is dropped.
+pub fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+
+    for event in Parser::new_ext(markdown, Options::all()) {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(&content),
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak => text.push('\n'),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock) => {
+                text.push_str("\n\n")
+            }
+            Event::End(TagEnd::Item) => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    text.trim().to_string()
+}
@@ -0,0 +1,73 @@
+pub mod markdown;
+
+use std::borrow::Cow;
+
+use ammonia::Builder;
+use reqwest::Url;
+
+use crate::api::rest::RepositoryResponse;
+
+/// Branch assumed when a repository doesn't report its default branch.
+const FALLBACK_BRANCH: &str = "main";
+
+/// Bases that relative URLs in a repository's README are resolved against.
+struct RepositoryBases {
+    /// Files linked to are shown on GitHub.
+    blob: Url,
+    /// Images are fetched directly.
+    raw: Url,
+}
+
+impl RepositoryBases {
+    fn new(repository: &RepositoryResponse) -> Option<Self> {
+        let branch = repository
+            .default_branch
+            .as_deref()
+            .unwrap_or(FALLBACK_BRANCH);
+        let base = |kind: &str| {
+            format!("{}/{kind}/{branch}/", repository.html_url)
+                .parse()
+                .ok()
+        };
+
+        Some(Self {
+            blob: base("blob")?,
+            raw: base("raw")?,
+        })
+    }
+}
+
+/// Resolves a relative URL, leaving absolute and fragment-only URLs alone. Root-relative
+/// URLs are relative to the root of the repository, as they are on GitHub.
+fn resolve<'u>(base: &Url, url: &'u str) -> Cow<'u, str> {
+    if url.is_empty() || url.starts_with('#') || url.starts_with("//") || Url::parse(url).is_ok() {
+        return Cow::Borrowed(url);
+    }
+
+    base.join(url.trim_start_matches('/'))
+        .map(|resolved| Cow::Owned(resolved.to_string()))
+        .unwrap_or(Cow::Borrowed(url))
+}
+
+/// Prepares README HTML rendered by GitHub to be embedded on another site, by pointing relative
+/// links and images at the repository's default branch and removing anything not on ammonia's
+/// allowlist of safe tags and attributes.
+pub fn embeddable(html: &str, repository: &RepositoryResponse) -> String {
+    let mut builder = Builder::default();
+    // Alignment is commonly used to centre logos and badges
+    builder.add_tag_attributes("p", ["align"]);
+    builder.add_tag_attributes("div", ["align"]);
+    builder.add_tag_attributes("h1", ["align"]);
+
+    if let Some(bases) = RepositoryBases::new(repository) {
+        builder.attribute_filter(move |element, attribute, value| {
+            Some(match (element, attribute) {
+                ("img", "src") => resolve(&bases.raw, value),
+                ("a", "href") => resolve(&bases.blob, value),
+                _ => Cow::Borrowed(value),
+            })
+        });
+    }
+
+    builder.clean(html).to_string()
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use shared::plugin::{
+    DataPlugin, DataQuery, PluginError, PluginIdentifier, ProjectReleasesResponse, ProjectResponse,
+    ReleaseResponse, ReleasesResponse,
+};
+
+use crate::{
+    api::{
+        rest::{RepositoryResponse, RestApi},
+        GithubApiError,
+    },
+    projects::repo_topics::PORTFOLIO_TOPIC,
+};
+
+/// Number of recent releases returned when no `limit` parameter is given.
+const DEFAULT_LIMIT: usize = 5;
+/// Largest page size accepted by the API.
+const MAX_LIMIT: usize = 100;
+/// Maximum number of releases requests in flight for a single plugin run.
+const CONCURRENCY: usize = 8;
+
+/// Gets the most recent releases of a repository, excluding drafts.
+pub async fn project_releases(
+    rest_api: &RestApi,
+    access_token: &str,
+    repository: &RepositoryResponse,
+    limit: usize,
+) -> Result<ProjectReleasesResponse, GithubApiError> {
+    let (recent, latest) = futures::try_join!(
+        rest_api
+            .repositories
+            .list_releases(access_token, &repository.full_name, limit),
+        latest_release(rest_api, access_token, repository),
+    )?;
+
+    Ok(ProjectReleasesResponse {
+        project: repository.name.clone(),
+        full_name: repository.full_name.clone(),
+        private: repository.private,
+        latest,
+        recent: recent
+            .into_iter()
+            .filter(|release| !release.draft)
+            .map(ReleaseResponse::from)
+            .collect(),
+    })
+}
+
+/// Gets the release GitHub shows as latest, which may be older than every recent release.
+async fn latest_release(
+    rest_api: &RestApi,
+    access_token: &str,
+    repository: &RepositoryResponse,
+) -> Result<Option<ReleaseResponse>, GithubApiError> {
+    match rest_api
+        .repositories
+        .get_latest_release(access_token, &repository.full_name)
+        .await
+    {
+        Ok(release) => Ok(Some(release.into())),
+        // A repository without any full releases has no latest release
+        Err(GithubApiError::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Gets the releases of several repositories concurrently, in the same order.
+pub async fn all_project_releases(
+    rest_api: &RestApi,
+    access_token: &str,
+    repositories: &[RepositoryResponse],
+    limit: usize,
+) -> Result<Vec<ProjectReleasesResponse>, GithubApiError> {
+    // Futures are created up front, as mapping the stream lazily isn't `Send`
+    let requests = repositories
+        .iter()
+        .map(|repository| project_releases(rest_api, access_token, repository, limit))
+        .collect::<Vec<_>>();
+
+    stream::iter(requests)
+        .buffered(CONCURRENCY)
+        .try_collect()
+        .await
+}
+
+/// Embeds the latest release of each repository into its project if requested by a
+/// `release=latest` parameter. Projects must be in the same order as the repositories.
+pub async fn embed_latest_releases(
+    rest_api: &RestApi,
+    access_token: &str,
+    repositories: &[RepositoryResponse],
+    projects: &mut [ProjectResponse],
+    query: &DataQuery,
+) -> Result<(), GithubApiError> {
+    if query.get("release") != Some("latest") {
+        return Ok(());
+    }
+
+    // Futures are created up front, as mapping the stream lazily isn't `Send`
+    let requests = repositories
+        .iter()
+        .map(|repository| latest_release(rest_api, access_token, repository))
+        .collect::<Vec<_>>();
+
+    let releases = stream::iter(requests)
+        .buffered(CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+    for (project, latest) in projects.iter_mut().zip(releases) {
+        project.latest_release = latest;
+    }
+
+    Ok(())
+}
+
+/// Releases of each project in the user's portfolio.
+pub struct ReleasesPortfolio {
+    rest_api: Arc<RestApi>,
+}
+
+impl ReleasesPortfolio {
+    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for ReleasesPortfolio {
+    type D = ReleasesResponse;
+
+    async fn get_data(
+        &self,
+        _username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let limit = query
+            .get("limit")
+            .map(|limit| {
+                limit
+                    .parse()
+                    .ok()
+                    .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+                    .ok_or_else(|| {
+                        PluginError::InvalidParameter(
+                            "limit".to_string(),
+                            format!("expected a number from 1 to {MAX_LIMIT}, found `{limit}`"),
+                        )
+                    })
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_LIMIT);
+
+        let repositories = self
+            .rest_api
+            .search
+            .repositories
+            .by_topics(auth_token, &[PORTFOLIO_TOPIC.to_string()])
+            .await?;
+
+        Ok(all_project_releases(&self.rest_api, auth_token, &repositories, limit).await?)
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("repo_topics")
+    }
+
+    fn params(&self) -> &[&str] {
+        &["limit"]
+    }
+}
@@ -0,0 +1,72 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::async_trait;
+use shared::plugin::{
+    DataPlugin, DataQuery, PluginError, PluginIdentifier, PostResponse, TagResponse, TagsResponse,
+};
+
+use crate::{
+    api::rest::RestApi,
+    posts::{
+        issues::{search_posts, POST_LABEL},
+        lifecycle::{self, is_lifecycle_label},
+    },
+};
+
+pub struct TagsIssues {
+    rest_api: Arc<RestApi>,
+}
+
+impl TagsIssues {
+    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+        Self {
+            rest_api: Arc::clone(rest_api),
+        }
+    }
+}
+
+#[async_trait]
+impl DataPlugin for TagsIssues {
+    type D = TagsResponse;
+
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        let mut tags = HashMap::<String, TagResponse>::new();
+
+        for issue in search_posts(&self.rest_api, username, auth_token, &[]).await? {
+            // Labels are consumed when converting to a post, so keep a copy for the tag details
+            let labels = issue.labels.clone();
+            let post = PostResponse::from(issue);
+            if !lifecycle::is_listed(&post, query) {
+                continue;
+            }
+
+            for label in labels
+                .into_iter()
+                .filter(|label| label.name != POST_LABEL && !is_lifecycle_label(&label.name))
+            {
+                tags.entry(label.name.clone())
+                    .or_insert_with(|| TagResponse {
+                        name: label.name,
+                        count: 0,
+                        color: Some(format!("#{}", label.color)),
+                        description: label.description,
+                    })
+                    .count += 1;
+            }
+        }
+
+        let mut tags = tags.into_values().collect::<Vec<_>>();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(tags)
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("issues")
+    }
+}
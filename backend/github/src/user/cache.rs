@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::RwLock;
+
+use crate::{
+    api::{
+        graphql::{GraphqlApi, ProfileResponse},
+        GithubApiError,
+    },
+    projects::languages::LanguageCache,
+};
+
+/// How long a profile is reused for, so that the plugins rendering a page share one request
+/// without serving stale data once it has changed.
+const MAX_AGE: Duration = Duration::from_secs(10);
+
+struct CachedProfile {
+    profile: Arc<ProfileResponse>,
+    fetched_at: Instant,
+}
+
+/// Fetches the profile, repositories and pinned items of users together, for the plugins that
+/// each return part of them.
+pub struct ProfileCache {
+    graphql_api: Arc<GraphqlApi>,
+    languages: Arc<LanguageCache>,
+    entries: RwLock<HashMap<String, CachedProfile>>,
+}
+
+impl ProfileCache {
+    pub fn new(graphql_api: &Arc<GraphqlApi>, languages: &Arc<LanguageCache>) -> Self {
+        Self {
+            graphql_api: Arc::clone(graphql_api),
+            languages: Arc::clone(languages),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get(
+        &self,
+        username: &str,
+        access_token: &str,
+    ) -> Result<Arc<ProfileResponse>, GithubApiError> {
+        if let Some(entry) = self.entries.read().await.get(username) {
+            if entry.fetched_at.elapsed() < MAX_AGE {
+                return Ok(Arc::clone(&entry.profile));
+            }
+        }
+
+        let profile = Arc::new(self.graphql_api.profile.get(access_token).await?);
+
+        // Languages are fetched along with repositories, so don't need requesting separately
+        for repository in &profile.repositories {
+            self.languages
+                .insert(&repository.repository, &repository.languages)
+                .await;
+        }
+
+        self.entries.write().await.insert(
+            username.to_string(),
+            CachedProfile {
+                profile: Arc::clone(&profile),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        Ok(profile)
+    }
+}
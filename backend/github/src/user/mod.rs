@@ -1,3 +1,5 @@
+mod cache;
 mod profile;
 
+pub use cache::*;
 pub use profile::*;
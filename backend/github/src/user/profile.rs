@@ -1,18 +1,18 @@
 use std::sync::Arc;
 
 use axum::async_trait;
-use shared::plugin::{DataPlugin, PluginError, PluginIdentifier, UserResponse};
+use shared::plugin::{DataPlugin, DataQuery, PluginError, PluginIdentifier, UserResponse};
 
-use crate::api::rest::RestApi;
+use super::ProfileCache;
 
 pub struct GithubUserProfile {
-    rest_api: Arc<RestApi>,
+    profiles: Arc<ProfileCache>,
 }
 
 impl GithubUserProfile {
-    pub fn new(rest_api: &Arc<RestApi>) -> Self {
+    pub fn new(profiles: &Arc<ProfileCache>) -> Self {
         Self {
-            rest_api: Arc::clone(rest_api),
+            profiles: Arc::clone(profiles),
         }
     }
 }
@@ -21,8 +21,19 @@ impl GithubUserProfile {
 impl DataPlugin for GithubUserProfile {
     type D = UserResponse;
 
-    async fn get_data(&self, _username: &str, auth_token: &str) -> Result<Self::D, PluginError> {
-        Ok(self.rest_api.user.get(auth_token).await?.into())
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        _query: &DataQuery,
+    ) -> Result<Self::D, PluginError> {
+        Ok(self
+            .profiles
+            .get(username, auth_token)
+            .await?
+            .user
+            .clone()
+            .into())
     }
 
     fn get_identifier(&self) -> PluginIdentifier {
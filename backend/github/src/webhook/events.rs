@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use shared::plugin::{InvalidateData, InvalidationPayload, PluginIdentifier, WebhookPlugin};
+use thiserror::Error;
+
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+const EVENT_HEADER: &str = "x-github-event";
+
+pub struct GithubWebhook {
+    secret: Arc<String>,
+}
+
+impl GithubWebhook {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: Arc::new(secret.to_string()),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    invalidate_data: InvalidateData,
+    source_identifier: Arc<String>,
+    secret: Arc<String>,
+}
+
+#[derive(Debug, Error)]
+enum WebhookHandlerError {
+    #[error("missing or invalid signature")]
+    Signature,
+    #[error("missing event header")]
+    MissingEvent,
+    #[error("unable to parse payload: {0}")]
+    Payload(#[from] serde_json::Error),
+    #[error("channel error")]
+    Channel,
+}
+impl IntoResponse for WebhookHandlerError {
+    fn into_response(self) -> Response {
+        eprintln!("{self}");
+        match self {
+            WebhookHandlerError::Signature => StatusCode::UNAUTHORIZED,
+            WebhookHandlerError::MissingEvent | WebhookHandlerError::Payload(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            WebhookHandlerError::Channel => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+        .into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct OwnerPayload {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct RepositoryPayload {
+    name: String,
+    owner: OwnerPayload,
+}
+
+/// Fields common to every event that is handled.
+#[derive(Deserialize)]
+struct EventPayload {
+    repository: Option<RepositoryPayload>,
+}
+
+impl WebhookPlugin for GithubWebhook {
+    fn register_routes(
+        &self,
+        source_identifier: &str,
+        invalidate_data: InvalidateData,
+    ) -> Router<()> {
+        let state = WebhookState {
+            invalidate_data,
+            source_identifier: Arc::new(source_identifier.to_string()),
+            secret: Arc::clone(&self.secret),
+        };
+
+        Router::new().route(
+            "/",
+            post(move |headers, body| handle_event(state, headers, body)),
+        )
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        PluginIdentifier::new("events")
+    }
+}
+
+/// Verifies the `X-Hub-Signature-256` header, which is a HMAC of the body using the shared secret.
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    let Some(signature) = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|signature| signature.to_str().ok())
+        .and_then(|signature| signature.strip_prefix("sha256="))
+        .and_then(|signature| hex::decode(signature).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Request types affected by an event. Posts, tags and the blurb all come from the user's
+/// profile repository, which is named after them.
+fn affected_request_types(event: &str, repository: &RepositoryPayload) -> &'static [&'static str] {
+    let profile_repository = repository.name == repository.owner.login;
+
+    match (event, profile_repository) {
+        ("issues" | "label" | "discussion", true) => &["posts", "tags"],
+        ("push", true) => &[
+            "blurb",
+            "posts",
+            "tags",
+            "projects",
+            "languages",
+            "activity",
+        ],
+        ("push", _) => &["projects", "languages", "activity"],
+        ("release", _) => &["projects", "activity", "releases"],
+        ("repository", _) => &["projects"],
+        _ => &[],
+    }
+}
+
+async fn handle_event(
+    state: WebhookState,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, WebhookHandlerError> {
+    if !verify_signature(&state.secret, &headers, &body) {
+        return Err(WebhookHandlerError::Signature);
+    }
+
+    let event = headers
+        .get(EVENT_HEADER)
+        .and_then(|event| event.to_str().ok())
+        .ok_or(WebhookHandlerError::MissingEvent)?;
+
+    let Some(repository) = serde_json::from_slice::<EventPayload>(&body)?.repository else {
+        // Events such as `ping` aren't associated with a repository
+        return Ok(StatusCode::NO_CONTENT);
+    };
+
+    let request_types = affected_request_types(event, &repository);
+    if request_types.is_empty() {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    state
+        .invalidate_data
+        .send(InvalidationPayload::new(
+            &state.source_identifier,
+            &repository.owner.login,
+            request_types,
+        ))
+        .map_err(|_| WebhookHandlerError::Channel)?;
+
+    Ok(StatusCode::ACCEPTED)
+}
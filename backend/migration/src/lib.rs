@@ -1,12 +1,20 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20230604_000001_create_table;
+mod m20261019_000001_add_preview_token;
+mod m20261019_000002_add_privacy_policy;
+mod m20261019_000003_add_settings_token;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20230604_000001_create_table::Migration)]
+        vec![
+            Box::new(m20230604_000001_create_table::Migration),
+            Box::new(m20261019_000001_add_preview_token::Migration),
+            Box::new(m20261019_000002_add_privacy_policy::Migration),
+            Box::new(m20261019_000003_add_settings_token::Migration),
+        ]
     }
 }
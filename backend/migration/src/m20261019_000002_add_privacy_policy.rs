@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Private repositories are hidden unless the user chooses otherwise
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserSource::Table)
+                    .add_column(
+                        ColumnDef::new(UserSource::PrivateRepos)
+                            .string()
+                            .not_null()
+                            .default("hide"),
+                    )
+                    .add_column(
+                        ColumnDef::new(UserSource::PrivateRepoAllowlist)
+                            .json_binary()
+                            .not_null()
+                            .extra("DEFAULT '[]'::jsonb".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserSource::Table)
+                    .drop_column(UserSource::PrivateRepos)
+                    .drop_column(UserSource::PrivateRepoAllowlist)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum UserSource {
+    Table,
+    PrivateRepos,
+    PrivateRepoAllowlist,
+}
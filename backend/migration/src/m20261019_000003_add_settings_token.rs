@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add a token authorising changes to settings, separate from the preview token
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserSource::Table)
+                    .add_column(
+                        ColumnDef::new(UserSource::SettingsToken)
+                            .string()
+                            .not_null()
+                            .extra("DEFAULT gen_random_uuid()::text".to_string()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserSource::Table)
+                    .drop_column(UserSource::SettingsToken)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum UserSource {
+    Table,
+    SettingsToken,
+}
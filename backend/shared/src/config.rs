@@ -0,0 +1,314 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use thiserror::Error;
+use toml::{Table, Value};
+use url::Url;
+
+/// Suffix of a key or variable naming a file to read the value from, so that secrets can be
+/// mounted as files rather than set directly.
+const FILE_SUFFIX: &str = "_file";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("unable to read config file {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("unable to parse config file {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("invalid configuration:\n{0}")]
+    Invalid(ConfigProblems),
+}
+
+/// A problem with a single configuration value.
+#[derive(Debug)]
+pub struct ConfigProblem {
+    /// Dotted path of the key in the config file.
+    pub key: String,
+    /// Environment variable overriding the key.
+    pub variable: String,
+    pub message: String,
+}
+
+impl Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.key, self.variable, self.message)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ConfigProblems(pub Vec<ConfigProblem>);
+
+impl Display for ConfigProblems {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for problem in &self.0 {
+            writeln!(f, "  {problem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A type that can be read from configuration, either as a string from an environment variable
+/// or file, or as a TOML value.
+pub trait ConfigValue: Sized {
+    fn parse(value: &str) -> Result<Self, String>;
+
+    fn from_toml(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::String(value) => Self::parse(value),
+            Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => {
+                Self::parse(&value.to_string())
+            }
+            _ => Err(format!("unexpected {}", value.type_str())),
+        }
+    }
+}
+
+macro_rules! from_str_config_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ConfigValue for $ty {
+                fn parse(value: &str) -> Result<Self, String> {
+                    <$ty as FromStr>::from_str(value.trim()).map_err(|e| e.to_string())
+                }
+            }
+        )*
+    };
+}
+
+from_str_config_value!(String, bool, u16, u32, u64, usize, IpAddr, Url, PathBuf);
+
+/// A comma separated list, or a TOML array.
+impl<T: ConfigValue> ConfigValue for Vec<T> {
+    fn parse(value: &str) -> Result<Self, String> {
+        value
+            .split(',')
+            .filter(|item| !item.trim().is_empty())
+            .map(T::parse)
+            .collect()
+    }
+
+    fn from_toml(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Array(items) => items.iter().map(T::from_toml).collect(),
+            value => Self::parse(&String::from_toml(value)?),
+        }
+    }
+}
+
+/// Comma separated `key=value` pairs, or a TOML table.
+impl<T: ConfigValue> ConfigValue for HashMap<String, T> {
+    fn parse(value: &str) -> Result<Self, String> {
+        value
+            .split(',')
+            .filter(|pair| !pair.trim().is_empty())
+            .map(|pair| {
+                let (key, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected `key=value`, found `{pair}`"))?;
+
+                Ok((key.trim().to_string(), T::parse(value)?))
+            })
+            .collect()
+    }
+
+    fn from_toml(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Table(table) => table
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), T::from_toml(value)?)))
+                .collect(),
+            value => Self::parse(&String::from_toml(value)?),
+        }
+    }
+}
+
+/// Configuration read from an optional TOML file, with each key overridden by an environment
+/// variable named after its path, e.g. `github.client_id` by `GITHUB_CLIENT_ID`. Either can
+/// instead name a file containing the value, with `client_id_file` or `GITHUB_CLIENT_ID_FILE`.
+///
+/// Problems are collected as values are read rather than returned immediately, so that they can
+/// all be reported at once by [`Config::finish`].
+pub struct Config {
+    file: Table,
+    environment: HashMap<String, String>,
+    /// Keys that have been read, used to find unrecognised keys in the file.
+    known: RefCell<HashSet<String>>,
+    problems: RefCell<Vec<ConfigProblem>>,
+}
+
+impl Config {
+    pub fn load(
+        path: Option<&Path>,
+        environment: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, ConfigError> {
+        let file = match path {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|e| ConfigError::Read(path.to_path_buf(), e))?
+                .parse::<Table>()
+                .map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?,
+            None => Table::new(),
+        };
+
+        Ok(Self {
+            file,
+            // Empty variables are treated as unset
+            environment: environment
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .collect(),
+            known: RefCell::new(HashSet::new()),
+            problems: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn root(&self) -> Section<'_> {
+        Section {
+            config: self,
+            path: Vec::new(),
+            table: Some(&self.file),
+        }
+    }
+
+    /// Reports every problem found whilst reading values, along with any keys in the file that
+    /// were never read.
+    pub fn finish(self) -> Result<(), ConfigError> {
+        let known = self.known.into_inner();
+        let mut problems = self.problems.into_inner();
+
+        let mut keys = Vec::new();
+        leaf_keys(&self.file, "", &mut keys);
+        problems.extend(
+            keys.into_iter()
+                .filter(|key| {
+                    !key.match_indices('.')
+                        .map(|(end, _)| &key[..end])
+                        .chain([key.as_str()])
+                        .any(|prefix| known.contains(prefix))
+                })
+                .map(|key| ConfigProblem {
+                    variable: variable(&key),
+                    key,
+                    message: "unrecognised key".to_string(),
+                }),
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(ConfigProblems(problems)))
+        }
+    }
+}
+
+fn leaf_keys(table: &Table, prefix: &str, keys: &mut Vec<String>) {
+    for (key, value) in table {
+        let key = format!("{prefix}{key}");
+        match value {
+            Value::Table(table) => leaf_keys(table, &format!("{key}."), keys),
+            _ => keys.push(key),
+        }
+    }
+}
+
+fn variable(key: &str) -> String {
+    key.replace('.', "_").to_uppercase()
+}
+
+/// A table within the configuration, such as the section for a source.
+pub struct Section<'c> {
+    config: &'c Config,
+    path: Vec<String>,
+    table: Option<&'c Table>,
+}
+
+impl<'c> Section<'c> {
+    pub fn section(&self, name: &str) -> Section<'c> {
+        Section {
+            config: self.config,
+            path: [self.path.as_slice(), &[name.to_string()]].concat(),
+            table: self
+                .table
+                .and_then(|table| table.get(name))
+                .and_then(Value::as_table),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        [self.path.as_slice(), &[key.to_string()]]
+            .concat()
+            .join(".")
+    }
+
+    /// Records a problem with a key, for validation that involves more than one value.
+    pub fn problem(&self, key: &str, message: impl Into<String>) {
+        let key = self.key(key);
+        self.config.problems.borrow_mut().push(ConfigProblem {
+            variable: variable(&key),
+            key,
+            message: message.into(),
+        });
+    }
+
+    fn read_file<T: ConfigValue>(&self, key: &str, path: &str) -> Option<T> {
+        fs::read_to_string(path)
+            .map_err(|e| format!("unable to read {path}: {e}"))
+            .and_then(|value| T::parse(&value))
+            .map_err(|message| self.problem(key, message))
+            .ok()
+    }
+
+    /// Reads a value if it is set, recording a problem if it is invalid.
+    pub fn optional<T: ConfigValue>(&self, key: &str) -> Option<T> {
+        let path = self.key(key);
+        let variable = variable(&path);
+        let file_key = format!("{key}{FILE_SUFFIX}");
+        {
+            let mut known = self.config.known.borrow_mut();
+            known.insert(path);
+            known.insert(self.key(&file_key));
+        }
+
+        let environment = &self.config.environment;
+        let parsed = if let Some(value) = environment.get(&variable) {
+            T::parse(value)
+        } else if let Some(path) = environment.get(&format!("{variable}_FILE")) {
+            return self.read_file(key, path);
+        } else if let Some(value) = self
+            .table
+            .and_then(|table| table.get(key))
+            .filter(|value| value.as_str() != Some(""))
+        {
+            T::from_toml(value)
+        } else if let Some(path) = self
+            .table
+            .and_then(|table| table.get(&file_key))
+            .and_then(Value::as_str)
+        {
+            return self.read_file(key, path);
+        } else {
+            return None;
+        };
+
+        parsed.map_err(|message| self.problem(key, message)).ok()
+    }
+
+    /// Reads a value, recording a problem if it is missing or invalid.
+    pub fn required<T: ConfigValue>(&self, key: &str) -> Option<T> {
+        let problems = self.config.problems.borrow().len();
+        let value = self.optional(key);
+        // An invalid value has already been reported
+        if value.is_none() && self.config.problems.borrow().len() == problems {
+            self.problem(key, "missing required value");
+        }
+
+        value
+    }
+}
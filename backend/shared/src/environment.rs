@@ -1,3 +0,0 @@
-use std::collections::HashMap;
-
-pub type Environment = HashMap<String, String>;
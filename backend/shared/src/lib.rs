@@ -1,4 +1,3 @@
-pub mod environment;
-pub mod macros;
+pub mod config;
 pub mod plugin;
 pub mod source;
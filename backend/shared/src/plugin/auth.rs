@@ -1,5 +1,5 @@
 use axum::Router;
-use thiserror::Error;
+use rand::{distributions::Alphanumeric, Rng};
 use tokio::sync::mpsc::UnboundedSender;
 use url::Url;
 
@@ -20,6 +20,10 @@ pub struct AuthTokenPayload {
     pub source: String,
     pub username: String,
     pub auth_token: String,
+    /// Token the user can present to preview unpublished content.
+    pub preview_token: String,
+    /// Token the user can present to change their settings.
+    pub settings_token: String,
 }
 impl AuthTokenPayload {
     pub fn new(source: &str, username: &str, auth_token: &str) -> Self {
@@ -27,6 +31,8 @@ impl AuthTokenPayload {
             source: source.to_string(),
             username: username.to_string(),
             auth_token: auth_token.to_string(),
+            preview_token: random_token(),
+            settings_token: random_token(),
         }
     }
 
@@ -35,12 +41,12 @@ impl AuthTokenPayload {
     }
 }
 
-pub type SaveAuthToken = UnboundedSender<AuthTokenPayload>;
-
-#[derive(Debug, Error)]
-pub enum SourceError {
-    #[error("missing environment variable {0}")]
-    MissingEnvVar(String),
-    #[error("invalid url: {0}")]
-    UrlParse(#[from] url::ParseError),
+fn random_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
 }
+
+pub type SaveAuthToken = UnboundedSender<AuthTokenPayload>;
@@ -1,12 +1,27 @@
+use super::{DataQuery, PluginError, PluginIdentifier, RequestType};
 use axum::async_trait;
-use serde::Serialize;
-
-use super::{PluginError, PluginIdentifier};
 
 #[async_trait]
 pub trait DataPlugin: Send + Sync {
-    type D: Serialize;
+    type D: RequestType;
 
-    async fn get_data(&self, username: &str, auth_token: &str) -> Result<Self::D, PluginError>;
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<Self::D, PluginError>;
     fn get_identifier(&self) -> PluginIdentifier;
+
+    /// Parameters the plugin can't run without. Plugins with required parameters only run on
+    /// request, rather than being refreshed in advance.
+    fn required_params(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Parameters the plugin reads, including required ones. Any others are dropped from
+    /// requests, so that they don't stop a stored result from being used.
+    fn params(&self) -> &[&str] {
+        self.required_params()
+    }
 }
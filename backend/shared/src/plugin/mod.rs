@@ -1,15 +1,22 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
-use serde::Serialize;
+use axum::{async_trait, http::StatusCode, response::IntoResponse, Json};
+use schemars::schema::RootSchema;
+use serde::{Serialize, Serializer};
 use std::{fmt::Display, ops::Deref};
 use thiserror::Error;
 
 mod auth;
 mod data;
+mod query;
+mod request_type;
 mod response;
+mod webhook;
 
 pub use auth::*;
 pub use data::*;
+pub use query::*;
+pub use request_type::*;
 pub use response::*;
+pub use webhook::*;
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct PluginIdentifier(String);
@@ -31,69 +38,135 @@ impl Display for PluginIdentifier {
     }
 }
 
-type UserPlugin = Box<dyn DataPlugin<D = UserResponse>>;
-type ProjectsPlugin = Box<dyn DataPlugin<D = ProjectsResponse>>;
-type BlurbPlugin = Box<dyn DataPlugin<D = BlurbResponse>>;
-type PostsPlugin = Box<dyn DataPlugin<D = PostsResponse>>;
-
-#[derive(Serialize)]
-#[serde(untagged)]
-pub enum PluginResponse {
-    User(UserResponse),
-    Projects(ProjectsResponse),
-    Blurb(BlurbResponse),
-    Posts(PostsResponse),
+/// Output of a plugin, which can be serialised without knowing its request type, or accessed
+/// directly by downcasting to the request type.
+pub struct PluginResponse {
+    request_type: &'static str,
+    data: Box<dyn ResponseData>,
 }
+
+impl PluginResponse {
+    pub fn new<R: RequestType>(data: R) -> Self {
+        Self {
+            request_type: R::NAME,
+            data: Box::new(data),
+        }
+    }
+
+    pub fn request_type(&self) -> &'static str {
+        self.request_type
+    }
+
+    pub fn downcast_ref<R: RequestType>(&self) -> Option<&R> {
+        self.data.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<R: RequestType>(&mut self) -> Option<&mut R> {
+        self.data.as_any_mut().downcast_mut()
+    }
+
+    /// Applies the visibility of each repository the response includes data from, failing if
+    /// the response can't be published at all.
+    pub fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        self.data.restrict(visibility)
+    }
+}
+
+impl Serialize for PluginResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        erased_serde::serialize(self.data.as_ref(), serializer)
+    }
+}
+
 impl IntoResponse for PluginResponse {
     fn into_response(self) -> axum::response::Response {
         Json(self).into_response()
     }
 }
 
-pub enum Plugin {
-    User(UserPlugin),
-    Projects(ProjectsPlugin),
-    Blurb(BlurbPlugin),
-    Posts(PostsPlugin),
+/// Runs a plugin of any request type, wrapping its output in a [`PluginResponse`].
+#[async_trait]
+trait AnyDataPlugin: Send + Sync {
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<PluginResponse, PluginError>;
+    fn get_identifier(&self) -> PluginIdentifier;
+    fn required_params(&self) -> &[&str];
+    fn params(&self) -> &[&str];
+}
+
+#[async_trait]
+impl<P: DataPlugin> AnyDataPlugin for P {
+    async fn get_data(
+        &self,
+        username: &str,
+        auth_token: &str,
+        query: &DataQuery,
+    ) -> Result<PluginResponse, PluginError> {
+        DataPlugin::get_data(self, username, auth_token, query)
+            .await
+            .map(PluginResponse::new)
+    }
+
+    fn get_identifier(&self) -> PluginIdentifier {
+        DataPlugin::get_identifier(self)
+    }
+
+    fn required_params(&self) -> &[&str] {
+        DataPlugin::required_params(self)
+    }
+
+    fn params(&self) -> &[&str] {
+        DataPlugin::params(self)
+    }
+}
+
+pub struct Plugin {
+    request_type: &'static str,
+    schema: fn() -> RootSchema,
+    plugin: Box<dyn AnyDataPlugin>,
 }
 
 impl Plugin {
-    pub fn request_type(&self) -> String {
-        match self {
-            Self::User(_) => "user",
-            Self::Projects(_) => "projects",
-            Self::Blurb(_) => "blurb",
-            Self::Posts(_) => "posts",
+    pub fn new<P: DataPlugin + 'static>(plugin: P) -> Self {
+        Self {
+            request_type: P::D::NAME,
+            schema: P::D::schema,
+            plugin: Box::new(plugin),
         }
-        .to_string()
     }
+
+    pub fn request_type(&self) -> String {
+        self.request_type.to_string()
+    }
+
+    /// Name and schema function of the plugin's request type, for registering.
+    pub(super) fn request_type_schema(&self) -> (&'static str, fn() -> RootSchema) {
+        (self.request_type, self.schema)
+    }
+
     pub async fn get_data(
         &self,
         username: &str,
         auth_token: &str,
+        query: &DataQuery,
     ) -> Result<PluginResponse, PluginError> {
-        macro_rules! expand_plugins {
-            ($($plugin:ident),*) => {
-                match self {
-                    $(
-                      Self::$plugin(plugin) => plugin.get_data(username, auth_token)
-                          .await
-                          .map(PluginResponse::$plugin)
-                    ),*
-                }
-            };
-        }
-
-        expand_plugins!(User, Projects, Blurb, Posts)
+        self.plugin.get_data(username, auth_token, query).await
     }
 
     pub fn get_identifier(&self) -> PluginIdentifier {
-        match self {
-            Self::User(plugin) => plugin.get_identifier(),
-            Self::Projects(plugin) => plugin.get_identifier(),
-            Self::Blurb(plugin) => plugin.get_identifier(),
-            Self::Posts(plugin) => plugin.get_identifier(),
-        }
+        self.plugin.get_identifier()
+    }
+
+    pub fn required_params(&self) -> &[&str] {
+        self.plugin.required_params()
+    }
+
+    pub fn params(&self) -> &[&str] {
+        self.plugin.params()
     }
 }
 
@@ -101,47 +174,20 @@ pub trait ToPlugin {
     fn to_plugin(self) -> Plugin;
 }
 
-// Trait magic: https://stackoverflow.com/a/40408431
-trait InnerToPlugin<P> {
-    fn to_plugin(plugin: P) -> Plugin;
-}
-
-impl<P> ToPlugin for P
-where
-    P: DataPlugin,
-    <P as DataPlugin>::D: InnerToPlugin<P>,
-{
+impl<P: DataPlugin + 'static> ToPlugin for P {
     fn to_plugin(self) -> Plugin {
-        <<P as DataPlugin>::D as InnerToPlugin<P>>::to_plugin(self)
+        Plugin::new(self)
     }
 }
 
-macro_rules! impl_to_plugin {
-    ($($plugin:ident: $response:ident),*) => {
-        $(
-            impl<P> InnerToPlugin<P> for $response
-            where
-                P: DataPlugin<D = $response> + 'static,
-            {
-                fn to_plugin(plugin: P) -> Plugin {
-                    Plugin::$plugin(Box::new(plugin))
-                }
-            }
-        )*
-    };
-}
-
-impl_to_plugin!(
-    User: UserResponse,
-    Projects: ProjectsResponse,
-    Blurb: BlurbResponse,
-    Posts: PostsResponse
-);
-
 #[derive(Debug, Error)]
 pub enum PluginError {
     #[error("requested resource is not found")]
     NotFound,
+    #[error("missing required parameter `{0}`")]
+    MissingParameter(String),
+    #[error("invalid value for parameter `{0}`: {1}")]
+    InvalidParameter(String, String),
     #[error("not authorised to access requested resource")]
     NotAuthorised,
     #[error("an external provider could not fulfill the request")]
@@ -154,6 +200,9 @@ impl IntoResponse for PluginError {
     fn into_response(self) -> axum::response::Response {
         match self {
             PluginError::NotFound => StatusCode::NOT_FOUND.into_response(),
+            PluginError::MissingParameter(_) | PluginError::InvalidParameter(_, _) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
             PluginError::NotAuthorised => StatusCode::UNAUTHORIZED.into_response(),
             PluginError::External => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
             PluginError::Internal => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
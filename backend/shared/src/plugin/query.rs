@@ -0,0 +1,45 @@
+use std::{collections::HashMap, sync::Mutex, time::SystemTime};
+
+/// Parameters supplied with a data request, passed through to the plugin.
+#[derive(Debug, Default)]
+pub struct DataQuery {
+    params: HashMap<String, String>,
+    preview: bool,
+    /// Earliest time at which the plugin's output is known to change, reported by the plugin.
+    expires_at: Mutex<Option<SystemTime>>,
+}
+
+impl DataQuery {
+    pub fn new(params: HashMap<String, String>, preview: bool) -> Self {
+        Self {
+            params,
+            preview,
+            expires_at: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(String::as_str)
+    }
+
+    /// Whether the request was authenticated with the user's preview token, allowing unpublished
+    /// content to be returned.
+    pub fn preview(&self) -> bool {
+        self.preview
+    }
+
+    /// Records that the output will change at a time, such as when a scheduled post is
+    /// published, so that it isn't stored beyond then. The earliest time given is kept.
+    pub fn expires_at(&self, at: SystemTime) {
+        if let Ok(mut expires_at) = self.expires_at.lock() {
+            *expires_at = Some(expires_at.map_or(at, |current| current.min(at)));
+        }
+    }
+
+    pub fn expiry(&self) -> Option<SystemTime> {
+        self.expires_at
+            .lock()
+            .ok()
+            .and_then(|expires_at| *expires_at)
+    }
+}
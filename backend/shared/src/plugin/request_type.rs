@@ -0,0 +1,175 @@
+use std::{any::Any, collections::BTreeMap};
+
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::Serialize;
+
+use super::{
+    ActivityResponse, BlurbResponse, LanguagesResponse, Plugin, PluginError, PostsResponse,
+    ProjectDetailResponse, ProjectsResponse, ReleasesResponse, TagsResponse, UserResponse,
+};
+
+/// How data from a repository may be published, as decided by the user's privacy policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Published in full.
+    Public,
+    /// Published with only non-identifying fields, such as languages.
+    Redacted,
+    /// Omitted entirely.
+    Hidden,
+}
+
+/// Decides the visibility of a repository from its name including the owner, such as
+/// `octocat/hello-world`, and whether it is private.
+pub type VisibilityOf<'a> = &'a dyn Fn(&str, bool) -> Visibility;
+
+/// A kind of data that plugins provide, declared by implementing this trait on its response type.
+/// Crates other than this one can declare their own request types, and a plugin's request type is
+/// determined by its response.
+pub trait RequestType: Serialize + JsonSchema + Send + Sync + 'static {
+    /// Name of the request type, used in request paths.
+    const NAME: &'static str;
+
+    /// JSON schema of the response.
+    fn schema() -> RootSchema {
+        schema_for!(Self)
+    }
+
+    /// Applies the visibility of each repository the response includes data from. There is no
+    /// default, so that every request type decides how the user's privacy policy applies to it.
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError>;
+}
+
+/// Declares request types whose responses don't include data from repositories, so are left
+/// untouched by the privacy policy.
+macro_rules! request_types {
+    ($($response:ty: $name:literal),*) => {
+        $(
+            impl RequestType for $response {
+                const NAME: &'static str = $name;
+
+                fn restrict(&mut self, _visibility: VisibilityOf) -> Result<(), PluginError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+request_types!(
+    UserResponse: "user",
+    BlurbResponse: "blurb",
+    PostsResponse: "posts",
+    TagsResponse: "tags"
+);
+
+impl RequestType for ProjectsResponse {
+    const NAME: &'static str = "projects";
+
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        self.retain_mut(|project| match project.visibility(visibility) {
+            Visibility::Public => true,
+            Visibility::Redacted => {
+                project.redact();
+                true
+            }
+            Visibility::Hidden => false,
+        });
+
+        Ok(())
+    }
+}
+
+impl RequestType for ProjectDetailResponse {
+    const NAME: &'static str = "project";
+
+    /// A project requested individually is only found if it is published in full.
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        match self.project.visibility(visibility) {
+            Visibility::Public => Ok(()),
+            Visibility::Redacted | Visibility::Hidden => Err(PluginError::NotFound),
+        }
+    }
+}
+
+impl RequestType for ReleasesResponse {
+    const NAME: &'static str = "releases";
+
+    /// Releases identify their project, so are only included if it is published in full.
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        self.retain(|releases| {
+            visibility(&releases.full_name, releases.private) == Visibility::Public
+        });
+
+        Ok(())
+    }
+}
+
+impl RequestType for ActivityResponse {
+    const NAME: &'static str = "activity";
+
+    /// Events identify their repository, so are only included if it is published in full.
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        self.events
+            .retain(|event| visibility(&event.repo, event.private) == Visibility::Public);
+
+        Ok(())
+    }
+}
+
+impl RequestType for LanguagesResponse {
+    const NAME: &'static str = "languages";
+
+    /// Languages aren't identifying, so redacted repositories are still counted.
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        self.retain_repositories(|repository| {
+            visibility(&repository.full_name, repository.private) != Visibility::Hidden
+        });
+
+        Ok(())
+    }
+}
+
+/// Response data of any request type.
+pub(super) trait ResponseData: erased_serde::Serialize + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError>;
+}
+
+impl<R: RequestType> ResponseData for R {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn restrict(&mut self, visibility: VisibilityOf) -> Result<(), PluginError> {
+        RequestType::restrict(self, visibility)
+    }
+}
+
+/// Every request type provided by a set of plugins, along with its schema.
+#[derive(Default)]
+pub struct RequestTypes(BTreeMap<&'static str, fn() -> RootSchema>);
+
+impl RequestTypes {
+    pub fn register<R: RequestType>(&mut self) {
+        self.0.insert(R::NAME, R::schema);
+    }
+
+    pub fn register_plugin(&mut self, plugin: &Plugin) {
+        let (name, schema) = plugin.request_type_schema();
+        self.0.insert(name, schema);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.keys().copied()
+    }
+
+    pub fn schema(&self, name: &str) -> Option<RootSchema> {
+        self.0.get(name).map(|schema| schema())
+    }
+}
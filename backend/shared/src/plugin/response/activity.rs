@@ -0,0 +1,48 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ContributionDayResponse {
+    /// Date in the form `YYYY-MM-DD`.
+    pub date: String,
+    pub count: usize,
+    /// Relative activity of the day, from 0 (none) to 4 (most active).
+    pub level: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ContributionCalendarResponse {
+    pub total: usize,
+    /// Every day in the calendar, oldest first.
+    pub days: Vec<ContributionDayResponse>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Push,
+    PullRequest,
+    Release,
+    Issue,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ActivityEventResponse {
+    pub kind: ActivityKind,
+    /// What happened, such as `opened`, `merged` or `published`.
+    pub action: String,
+    /// Repository the event happened in, including its owner.
+    pub repo: String,
+    #[serde(skip)]
+    pub private: bool,
+    pub title: String,
+    pub url: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ActivityResponse {
+    pub calendar: ContributionCalendarResponse,
+    /// Recent events, newest first.
+    pub events: Vec<ActivityEventResponse>,
+}
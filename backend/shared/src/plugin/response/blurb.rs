@@ -1,12 +1,27 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BlurbFormat {
+    Html,
+    Markdown,
+    /// Plain text, without any markup.
+    Text,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct BlurbResponse {
-    blurb: String,
+    pub blurb: String,
+    pub format: BlurbFormat,
 }
 
+/// A blurb given as a plain string is HTML, the default format.
 impl From<String> for BlurbResponse {
     fn from(s: String) -> Self {
-        Self { blurb: s }
+        Self {
+            blurb: s,
+            format: BlurbFormat::Html,
+        }
     }
 }
@@ -0,0 +1,84 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LanguageResponse {
+    pub name: String,
+    /// Size of code written in the language.
+    pub bytes: usize,
+    /// Proportion of bytes written in the language, from 0 to 100.
+    pub percentage: f64,
+}
+
+impl LanguageResponse {
+    /// Builds a breakdown from the bytes written in each language, largest first. Languages that
+    /// appear more than once are combined.
+    pub fn from_bytes(bytes: impl IntoIterator<Item = (String, usize)>) -> Vec<Self> {
+        let mut combined = Vec::<(String, usize)>::new();
+        for (name, bytes) in bytes {
+            match combined.iter_mut().find(|(existing, _)| *existing == name) {
+                Some((_, total)) => *total += bytes,
+                None => combined.push((name, bytes)),
+            }
+        }
+
+        combined.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+
+        let total = combined
+            .iter()
+            .map(|(_, bytes)| bytes)
+            .sum::<usize>()
+            .max(1) as f64;
+        combined
+            .into_iter()
+            .map(|(name, bytes)| Self {
+                name,
+                bytes,
+                percentage: (bytes as f64 / total * 1000.0).round() / 10.0,
+            })
+            .collect()
+    }
+}
+
+/// Bytes written in each language in a repository.
+#[derive(Debug)]
+pub struct RepositoryLanguagesResponse {
+    /// Name of the repository including its owner, such as `octocat/hello-world`.
+    pub full_name: String,
+    pub private: bool,
+    pub languages: Vec<LanguageResponse>,
+}
+
+/// Languages combined across several repositories, serialised as the combined breakdown. Each
+/// repository is kept so that the breakdown can be recalculated without some of them.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct LanguagesResponse {
+    languages: Vec<LanguageResponse>,
+    #[serde(skip)]
+    repositories: Vec<RepositoryLanguagesResponse>,
+}
+
+impl LanguagesResponse {
+    pub fn new(repositories: Vec<RepositoryLanguagesResponse>) -> Self {
+        Self {
+            languages: Self::combine(&repositories),
+            repositories,
+        }
+    }
+
+    fn combine(repositories: &[RepositoryLanguagesResponse]) -> Vec<LanguageResponse> {
+        LanguageResponse::from_bytes(repositories.iter().flat_map(|repository| {
+            repository
+                .languages
+                .iter()
+                .map(|language| (language.name.clone(), language.bytes))
+        }))
+    }
+
+    /// Removes repositories from the breakdown.
+    pub fn retain_repositories(&mut self, f: impl FnMut(&RepositoryLanguagesResponse) -> bool) {
+        self.repositories.retain(f);
+        self.languages = Self::combine(&self.repositories);
+    }
+}
@@ -1,9 +1,17 @@
+mod activity;
 mod blurb;
+mod languages;
 mod posts;
 mod projects;
+mod releases;
+mod tags;
 mod user;
 
+pub use activity::*;
 pub use blurb::*;
+pub use languages::*;
 pub use posts::*;
 pub use projects::*;
+pub use releases::*;
+pub use tags::*;
 pub use user::*;
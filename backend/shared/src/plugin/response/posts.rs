@@ -1,14 +1,55 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Published,
+    /// Publish date is in the future.
+    Scheduled,
+    /// Published, but only available when requested by slug.
+    Unlisted,
+    Draft,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TableOfContentsEntry {
+    pub level: u8,
+    pub title: String,
+    /// Anchor matching the heading's ID in rendered HTML.
+    pub anchor: String,
+}
+
+#[derive(Serialize, JsonSchema)]
 pub struct PostResponse {
-    pub number: usize,
+    /// Issue or discussion number, for sources that have one.
+    pub number: Option<usize>,
+    pub slug: String,
+    pub status: PostStatus,
     pub title: String,
+    pub summary: Option<String>,
+    pub cover_image: Option<String>,
+    pub canonical_url: Option<String>,
     pub body: String,
+    pub excerpt: Option<String>,
+    pub word_count: Option<usize>,
+    /// Estimated reading time in minutes.
+    pub reading_time: Option<usize>,
+    pub table_of_contents: Option<Vec<TableOfContentsEntry>>,
     pub tags: Vec<String>,
+    /// Category the post was filed under, for sources that have them.
+    pub category: Option<String>,
+    /// Number of each kind of reaction, for sources that have them.
+    pub reactions: Option<BTreeMap<String, usize>>,
     pub created_at: String,
     pub updated_at: String,
+    pub published_at: String,
     pub original_link: String,
+    /// Problems found whilst parsing the post's front matter.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
 }
 
 pub type PostsResponse = Vec<PostResponse>;
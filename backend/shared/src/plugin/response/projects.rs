@@ -1,6 +1,14 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+use crate::plugin::{Visibility, VisibilityOf};
+
+use super::{LanguageResponse, ReleaseResponse};
+
+/// Name shown in place of a redacted private project.
+const REDACTED_NAME: &str = "Private project";
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct Repo {
     pub url: String,
     pub stars: usize,
@@ -9,14 +17,57 @@ pub struct Repo {
     pub issues: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ProjectResponse {
     pub name: String,
+    /// Name of the repository including its owner, such as `octocat/hello-world`, or `None` if
+    /// the project isn't a repository.
+    #[serde(skip)]
+    pub full_name: Option<String>,
+    pub private: bool,
     pub description: Option<String>,
     pub url: Option<String>,
     pub repo: Option<Repo>,
     pub tags: Vec<String>,
-    pub languages: Option<Vec<String>>,
+    pub languages: Option<Vec<LanguageResponse>>,
+    /// Most recent release, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_release: Option<ReleaseResponse>,
+}
+
+impl ProjectResponse {
+    pub fn visibility(&self, visibility: VisibilityOf) -> Visibility {
+        match &self.full_name {
+            Some(full_name) => visibility(full_name, self.private),
+            None if self.private => Visibility::Hidden,
+            None => Visibility::Public,
+        }
+    }
+
+    /// Removes every identifying field, leaving only those such as languages.
+    pub fn redact(&mut self) {
+        self.name = REDACTED_NAME.to_string();
+        self.description = None;
+        self.url = None;
+        self.repo = None;
+        self.tags = Vec::new();
+        self.latest_release = None;
+    }
 }
 
 pub type ProjectsResponse = Vec<ProjectResponse>;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProjectDetailResponse {
+    #[serde(flatten)]
+    pub project: ProjectResponse,
+    /// README rendered as HTML.
+    pub readme: Option<String>,
+    /// SPDX identifier of the license, such as `MIT`.
+    pub license: Option<String>,
+    pub created_at: Option<String>,
+    pub pushed_at: Option<String>,
+    pub default_branch: Option<String>,
+    pub open_pull_requests: Option<usize>,
+    pub topics: Vec<String>,
+}
@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ReleaseAssetResponse {
+    pub name: String,
+    /// Direct download link.
+    pub url: String,
+    pub content_type: String,
+    /// Size in bytes.
+    pub size: usize,
+    pub download_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ReleaseResponse {
+    pub tag: String,
+    pub name: Option<String>,
+    /// Release notes rendered as HTML.
+    pub notes: Option<String>,
+    pub url: String,
+    pub prerelease: bool,
+    pub published_at: Option<String>,
+    pub assets: Vec<ReleaseAssetResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProjectReleasesResponse {
+    /// Name of the project the releases belong to.
+    pub project: String,
+    /// Name of the repository including its owner, such as `octocat/hello-world`.
+    #[serde(skip)]
+    pub full_name: String,
+    #[serde(skip)]
+    pub private: bool,
+    /// Most recent release that isn't a prerelease.
+    pub latest: Option<ReleaseResponse>,
+    /// Most recent releases, including prereleases, newest first.
+    pub recent: Vec<ReleaseResponse>,
+}
+
+pub type ReleasesResponse = Vec<ProjectReleasesResponse>;
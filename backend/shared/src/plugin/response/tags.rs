@@ -0,0 +1,13 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TagResponse {
+    pub name: String,
+    /// Number of posts with the tag.
+    pub count: usize,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+pub type TagsResponse = Vec<TagResponse>;
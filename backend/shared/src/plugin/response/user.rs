@@ -1,7 +1,8 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct UserResponse {
     pub name: Option<String>,
     pub avatar: String,
@@ -0,0 +1,33 @@
+use axum::Router;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::PluginIdentifier;
+
+pub trait WebhookPlugin {
+    fn register_routes(
+        &self,
+        source_identifier: &str,
+        invalidate_data: InvalidateData,
+    ) -> Router<()>;
+    fn get_identifier(&self) -> PluginIdentifier;
+}
+
+/// Notification that a user's data has changed upstream, so any stored results for the given
+/// request types are out of date.
+#[derive(Debug, Clone)]
+pub struct InvalidationPayload {
+    pub source: String,
+    pub username: String,
+    pub request_types: Vec<String>,
+}
+impl InvalidationPayload {
+    pub fn new(source: &str, username: &str, request_types: &[&str]) -> Self {
+        Self {
+            source: source.to_string(),
+            username: username.to_string(),
+            request_types: request_types.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+}
+
+pub type InvalidateData = UnboundedSender<InvalidationPayload>;
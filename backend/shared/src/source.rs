@@ -1,6 +1,8 @@
 use std::{fmt::Display, ops::Deref};
 
-use crate::plugin::{AuthPlugin, Plugin};
+use axum::async_trait;
+
+use crate::plugin::{AuthPlugin, Plugin, WebhookPlugin};
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct SourceIdentifier(String);
@@ -22,9 +24,17 @@ impl Display for SourceIdentifier {
     }
 }
 
+/// Checks that the upstream service a source fetches data from can be reached.
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    async fn check(&self) -> Result<(), String>;
+}
+
 pub trait Source {
     fn get_identifier(&self) -> SourceIdentifier;
 
     fn get_plugins(&self) -> Vec<Plugin>;
     fn get_auth_plugins(&self) -> Vec<Box<dyn AuthPlugin>>;
+    fn get_webhook_plugins(&self) -> Vec<Box<dyn WebhookPlugin>>;
+    fn get_health_check(&self) -> Box<dyn HealthCheck>;
 }
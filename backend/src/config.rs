@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use github::GithubConfig;
+use reqwest::Url;
+use shared::config::{Config, ConfigError, ConfigProblems};
+
+use crate::{images::ImageConfig, scheduler::SchedulerConfig, server::ServerConfig};
+
+/// File read if no other is given with `--config` or `CONFIG_FILE`.
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+pub struct Args {
+    /// Validate the configuration and exit, without connecting to anything.
+    pub check_config: bool,
+    pub config_file: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        let mut args = Self {
+            check_config: false,
+            config_file: None,
+        };
+
+        let mut arguments = std::env::args().skip(1);
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--check-config" => args.check_config = true,
+                "--config" => {
+                    args.config_file =
+                        Some(arguments.next().ok_or("`--config` requires a path")?.into())
+                }
+                argument => return Err(format!("unrecognised argument `{argument}`")),
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// The file given on the command line, otherwise by `CONFIG_FILE`, otherwise the default if
+    /// it exists.
+    fn config_file(&self) -> Option<PathBuf> {
+        self.config_file
+            .clone()
+            .or_else(|| {
+                std::env::var_os("CONFIG_FILE")
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from)
+            })
+            .or_else(|| {
+                let path = PathBuf::from(DEFAULT_CONFIG_FILE);
+                path.exists().then_some(path)
+            })
+    }
+}
+
+/// Configuration for the whole service, read from the config file and environment.
+pub struct AppConfig {
+    pub api_root: Url,
+    pub database_url: String,
+    pub server: ServerConfig,
+    /// Directory containing templates and `routes.json`.
+    pub template_dir: Option<PathBuf>,
+    pub scheduler: Option<SchedulerConfig>,
+    pub images: Option<ImageConfig>,
+    pub github: GithubConfig,
+}
+
+impl AppConfig {
+    /// Reads and validates every section, reporting all problems together.
+    pub fn load(args: &Args) -> Result<Self, ConfigError> {
+        let config = Config::load(args.config_file().as_deref(), std::env::vars())?;
+        let root = config.root();
+
+        let api_root = root.required::<Url>("api_root").filter(|api_root| {
+            let valid = matches!(api_root.scheme(), "http" | "https");
+            if !valid {
+                root.problem("api_root", "must be an absolute HTTP URL");
+            }
+
+            valid
+        });
+        let database_url = root.required("database_url");
+        let server = ServerConfig::from_config(&root.section("server"));
+        let template_dir = root.optional("template_dir");
+        let scheduler = SchedulerConfig::from_config(&root.section("refresh"));
+        let images = ImageConfig::from_config(&root.section("image"), api_root.as_ref());
+        let github = GithubConfig::from_config(&root.section("github"));
+
+        config.finish()?;
+
+        // Anything missing has already been reported by `finish`
+        let incomplete = || ConfigError::Invalid(ConfigProblems::default());
+        Ok(Self {
+            api_root: api_root.ok_or_else(incomplete)?,
+            database_url: database_url.ok_or_else(incomplete)?,
+            server,
+            template_dir,
+            scheduler,
+            images,
+            github: github.ok_or_else(incomplete)?,
+        })
+    }
+}
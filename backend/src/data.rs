@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+};
+use shared::{
+    plugin::{DataQuery, InvalidationPayload, Plugin, PluginIdentifier, PluginResponse},
+    source::SourceIdentifier,
+};
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+use tracing::{error, info, info_span, Instrument};
+
+use entities::{user_source, UserSource};
+
+use crate::{images::ImageUrls, privacy::PrivacyPolicy, Plugins};
+
+/// Maximum age of a stored result before it is fetched again.
+pub const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Identifies a stored result by request type, source, plugin and username.
+type CacheKey = (String, String, String, String);
+
+struct CacheEntry {
+    response: Arc<PluginResponse>,
+    expires_at: Instant,
+}
+
+/// Result of a plugin, along with how long it remains current.
+pub struct Fetched {
+    pub response: Arc<PluginResponse>,
+    pub max_age: Duration,
+}
+
+/// Time until a result expires, which is sooner than the maximum age if the plugin reported
+/// when its output changes.
+fn max_age(query: &DataQuery) -> Duration {
+    query
+        .expiry()
+        .map(|expiry| {
+            expiry
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+                .min(MAX_AGE)
+        })
+        .unwrap_or(MAX_AGE)
+}
+
+/// Compares a presented token with a stored one in constant time.
+fn token_matches(token: &str, expected: &str) -> bool {
+    token.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Runs plugins on behalf of users, storing the results of requests without parameters so that
+/// they can be served without contacting the source.
+pub struct DataService {
+    db: Arc<DatabaseConnection>,
+    plugins: Arc<Plugins>,
+    cache: RwLock<HashMap<CacheKey, CacheEntry>>,
+    /// Rewrites image URLs in plugin output to go through the image proxy.
+    images: Option<ImageUrls>,
+}
+
+impl DataService {
+    pub fn new(db: &Arc<DatabaseConnection>, plugins: Plugins, images: Option<ImageUrls>) -> Self {
+        Self {
+            db: Arc::clone(db),
+            plugins: Arc::new(plugins),
+            cache: RwLock::new(HashMap::new()),
+            images,
+        }
+    }
+
+    /// Lists the request type and identifier of every plugin provided by a source that can run
+    /// without parameters, and so can be refreshed in advance.
+    pub fn refreshable_plugins(&self, source_identifier: &str) -> Vec<(String, String)> {
+        self.plugins
+            .iter()
+            .filter(|((_, plugin_source, _), plugin)| {
+                **plugin_source == *source_identifier && plugin.required_params().is_empty()
+            })
+            .map(|((request_type, _, plugin_identifier), _)| {
+                (request_type.clone(), plugin_identifier.to_string())
+            })
+            .collect()
+    }
+
+    /// Lists the plugin identifiers for a request type provided by a source.
+    pub fn plugins_for(&self, request_type: &str, source_identifier: &str) -> Vec<String> {
+        self.plugins
+            .keys()
+            .filter(|(plugin_request_type, plugin_source, _)| {
+                plugin_request_type == request_type && **plugin_source == *source_identifier
+            })
+            .map(|(_, _, plugin_identifier)| plugin_identifier.to_string())
+            .collect()
+    }
+
+    pub async fn all_user_sources(&self) -> Result<Vec<user_source::Model>, DbErr> {
+        UserSource::find().all(self.db.as_ref()).await
+    }
+
+    /// Finds a user's source, checking that the token matches their settings token.
+    async fn authorised_user_source(
+        &self,
+        username: &str,
+        source_identifier: &str,
+        token: &str,
+    ) -> Result<user_source::Model, Response> {
+        UserSource::find_by_id((username.to_string(), source_identifier.to_string()))
+            .one(self.db.as_ref())
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+            .and_then(|user_source| user_source.ok_or(StatusCode::NOT_FOUND))
+            .and_then(|user_source| {
+                token_matches(token, &user_source.settings_token)
+                    .then_some(user_source)
+                    .ok_or(StatusCode::UNAUTHORIZED)
+            })
+            .map_err(IntoResponse::into_response)
+    }
+
+    pub async fn privacy_policy(
+        &self,
+        username: &str,
+        source_identifier: &str,
+        token: &str,
+    ) -> Result<PrivacyPolicy, Response> {
+        self.authorised_user_source(username, source_identifier, token)
+            .await
+            .map(|user_source| PrivacyPolicy::from_user_source(&user_source))
+    }
+
+    /// Saves a user's privacy policy, then replaces stored projects so that it applies
+    /// immediately.
+    pub async fn set_privacy_policy(
+        &self,
+        username: &str,
+        source_identifier: &str,
+        token: &str,
+        policy: &PrivacyPolicy,
+    ) -> Result<(), Response> {
+        let user_source = self
+            .authorised_user_source(username, source_identifier, token)
+            .await?;
+
+        let mut user_source: user_source::ActiveModel = user_source.into();
+        user_source.private_repos = Set(policy.private_repos());
+        user_source.private_repo_allowlist = Set(policy.allowlist_json());
+        user_source.update(self.db.as_ref()).await.map_err(|e| {
+            error!(message = "unable to save privacy policy", error = ?e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+        self.invalidate(InvalidationPayload::new(
+            source_identifier,
+            username,
+            &["projects", "project"],
+        ))
+        .await;
+
+        Ok(())
+    }
+
+    pub async fn user_sources(&self, username: &str) -> Result<Vec<user_source::Model>, Response> {
+        UserSource::find()
+            .filter(user_source::Column::Username.eq(username))
+            .all(self.db.as_ref())
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+    }
+
+    /// Finds the requested plugin along with the user's authentication for its source, and runs
+    /// it. A preview token must match the user's, and allows unpublished content to be returned.
+    pub async fn get(
+        &self,
+        request_type: &str,
+        source_identifier: &str,
+        plugin_identifier: &str,
+        username: &str,
+        params: HashMap<String, String>,
+        preview_token: Option<&str>,
+    ) -> Result<Fetched, Response> {
+        let key = (
+            request_type.to_string(),
+            source_identifier.to_string(),
+            plugin_identifier.to_string(),
+            username.to_string(),
+        );
+
+        // Parameters the plugin doesn't read are dropped, as they don't change its result
+        let plugin = self.plugin(&key).map_err(IntoResponse::into_response)?;
+        let params = params
+            .into_iter()
+            .filter(|(name, _)| plugin.params().contains(&name.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        // Only requests without parameters are stored, and previews never are
+        let stored = params.is_empty() && preview_token.is_none();
+        if stored {
+            if let Some(entry) = self.cache.read().await.get(&key) {
+                let now = Instant::now();
+                if entry.expires_at > now {
+                    return Ok(Fetched {
+                        response: Arc::clone(&entry.response),
+                        max_age: entry.expires_at - now,
+                    });
+                }
+            }
+        }
+
+        // Attempt to find authentication for the user and source
+        let user_source =
+            UserSource::find_by_id((username.to_string(), source_identifier.to_string()))
+                .one(self.db.as_ref())
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+                .and_then(|user_source| user_source.ok_or(StatusCode::UNAUTHORIZED))
+                .map_err(IntoResponse::into_response)?;
+
+        let preview = match preview_token {
+            Some(token) if token_matches(token, &user_source.preview_token) => true,
+            Some(_) => return Err(StatusCode::UNAUTHORIZED.into_response()),
+            None => false,
+        };
+        let query = DataQuery::new(params, preview);
+
+        let response = Arc::new(self.run(&key, &user_source, &query).await?);
+        let max_age = max_age(&query);
+        if stored {
+            self.store(key, &response, max_age).await;
+        }
+
+        Ok(Fetched { response, max_age })
+    }
+
+    /// Runs a plugin without parameters and stores the result, replacing any existing copy.
+    pub async fn refresh(
+        &self,
+        request_type: &str,
+        plugin_identifier: &str,
+        user_source: &user_source::Model,
+    ) -> Result<(), Response> {
+        let key = (
+            request_type.to_string(),
+            user_source.site.clone(),
+            plugin_identifier.to_string(),
+            user_source.username.clone(),
+        );
+
+        let query = DataQuery::default();
+        let response = Arc::new(self.run(&key, user_source, &query).await?);
+        self.store(key, &response, max_age(&query)).await;
+
+        Ok(())
+    }
+
+    /// Finds the plugin a result is requested from.
+    fn plugin(
+        &self,
+        (request_type, source_identifier, plugin_identifier, _): &CacheKey,
+    ) -> Result<&Plugin, StatusCode> {
+        self.plugins
+            .get(&(
+                request_type.to_string(),
+                SourceIdentifier::new(source_identifier),
+                PluginIdentifier::new(plugin_identifier),
+            ))
+            .ok_or(StatusCode::NOT_FOUND)
+    }
+
+    async fn run(
+        &self,
+        key: &CacheKey,
+        user_source: &user_source::Model,
+        query: &DataQuery,
+    ) -> Result<PluginResponse, Response> {
+        let mut response = self
+            .plugin(key)
+            .map_err(IntoResponse::into_response)?
+            .get_data(&user_source.username, &user_source.token, query)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        PrivacyPolicy::from_user_source(user_source)
+            .enforce(&mut response)
+            .map_err(IntoResponse::into_response)?;
+
+        if let Some(images) = &self.images {
+            let (_, source_identifier, ..) = key;
+            images.rewrite(
+                &mut response,
+                &format!("{source_identifier}/{}", user_source.username),
+            );
+        }
+
+        Ok(response)
+    }
+
+    async fn store(&self, key: CacheKey, response: &Arc<PluginResponse>, max_age: Duration) {
+        self.cache.write().await.insert(
+            key,
+            CacheEntry {
+                response: Arc::clone(response),
+                expires_at: Instant::now() + max_age,
+            },
+        );
+    }
+
+    /// Removes stored results affected by an upstream change, then fetches them again so that
+    /// subsequent requests are still served from storage.
+    pub async fn invalidate(&self, payload: InvalidationPayload) {
+        let span = info_span!(
+            "invalidate",
+            source = payload.source,
+            username = payload.username
+        );
+
+        async {
+            let invalidated = {
+                let mut cache = self.cache.write().await;
+                let keys = cache
+                    .keys()
+                    .filter(|(request_type, source_identifier, _, username)| {
+                        *source_identifier == payload.source
+                            && *username == payload.username
+                            && payload.request_types.contains(request_type)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                keys.into_iter()
+                    .filter(|key| cache.remove(key).is_some())
+                    .collect::<Vec<_>>()
+            };
+            info!(
+                message = "invalidated stored results",
+                count = invalidated.len()
+            );
+
+            for (request_type, source_identifier, plugin_identifier, username) in invalidated {
+                if let Err(response) = self
+                    .get(
+                        &request_type,
+                        &source_identifier,
+                        &plugin_identifier,
+                        &username,
+                        HashMap::new(),
+                        None,
+                    )
+                    .await
+                {
+                    error!(
+                        message = "unable to refresh result",
+                        request_type,
+                        plugin = plugin_identifier,
+                        status = ?response.status()
+                    );
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::future::join_all;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use serde::Serialize;
+use shared::source::{HealthCheck, SourceIdentifier};
+use tokio::{sync::Mutex, time};
+use tracing::warn;
+
+/// Time each dependency has to respond before it is reported as unavailable, so that a hanging
+/// dependency does not hold up the probe.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time a readiness result is reused for, so that frequent probes don't load the dependencies.
+const CACHE_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Unavailable,
+}
+
+/// Result of a check, which only includes the status as the probe is public. Errors are logged
+/// instead.
+#[derive(Clone, Serialize)]
+struct CheckResult {
+    status: Status,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Readiness {
+    status: Status,
+    database: CheckResult,
+    migrations: CheckResult,
+    sources: BTreeMap<String, CheckResult>,
+}
+
+impl IntoResponse for Readiness {
+    fn into_response(self) -> Response {
+        let status = match self.status {
+            Status::Ok => StatusCode::OK,
+            Status::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        (
+            status,
+            [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+            Json(self),
+        )
+            .into_response()
+    }
+}
+
+/// Runs a check with a timeout, logging why it failed and how long it took.
+async fn run(name: &str, check: impl Future<Output = Result<(), String>>) -> CheckResult {
+    let started = Instant::now();
+    let result = time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())));
+
+    match result {
+        Ok(()) => CheckResult { status: Status::Ok },
+        Err(error) => {
+            warn!(
+                message = "health check failed",
+                check = name,
+                error,
+                duration_ms = started.elapsed().as_millis() as u64
+            );
+            CheckResult {
+                status: Status::Unavailable,
+            }
+        }
+    }
+}
+
+/// Checks the dependencies the service needs to handle requests: the database, its schema, and
+/// the upstream of each source.
+pub struct Health {
+    db: Arc<DatabaseConnection>,
+    sources: Vec<(SourceIdentifier, Box<dyn HealthCheck>)>,
+    /// Most recent result and when it was checked.
+    cached: Mutex<Option<(Instant, Readiness)>>,
+}
+
+impl Health {
+    pub fn new(
+        db: &Arc<DatabaseConnection>,
+        sources: Vec<(SourceIdentifier, Box<dyn HealthCheck>)>,
+    ) -> Self {
+        Self {
+            db: Arc::clone(db),
+            sources,
+            cached: Mutex::new(None),
+        }
+    }
+
+    async fn check_database(&self) -> Result<(), String> {
+        let backend = self.db.get_database_backend();
+        self.db
+            .execute(Statement::from_string(backend, "SELECT 1".to_string()))
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Compares the migrations this build expects with those applied to the database.
+    async fn check_migrations(&self) -> Result<(), String> {
+        let applied = Migrator::get_migration_models(self.db.as_ref())
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|model| model.version)
+            .collect::<HashSet<_>>();
+        let pending = Migrator::migrations()
+            .iter()
+            .map(|migration| migration.name().to_string())
+            .filter(|name| !applied.contains(name))
+            .collect::<Vec<_>>();
+
+        match pending.is_empty() {
+            true => Ok(()),
+            false => Err(format!("pending migrations: {}", pending.join(", "))),
+        }
+    }
+
+    /// Reports the result of each check, reusing a recent result if there is one. Probes
+    /// arriving whilst the checks run wait for their result.
+    pub async fn readiness(&self) -> Readiness {
+        let mut cached = self.cached.lock().await;
+        if let Some((checked_at, readiness)) = cached.as_ref() {
+            if checked_at.elapsed() < CACHE_DURATION {
+                return readiness.clone();
+            }
+        }
+
+        let readiness = self.check().await;
+        *cached = Some((Instant::now(), readiness.clone()));
+
+        readiness
+    }
+
+    /// Runs every check at once, reporting the result of each.
+    async fn check(&self) -> Readiness {
+        let (database, migrations, sources) = tokio::join!(
+            run("database", self.check_database()),
+            run("migrations", self.check_migrations()),
+            join_all(self.sources.iter().map(|(identifier, check)| async move {
+                (identifier.to_string(), run(identifier, check.check()).await)
+            })),
+        );
+        let sources = sources.into_iter().collect::<BTreeMap<_, _>>();
+
+        let ready = [&database, &migrations]
+            .into_iter()
+            .chain(sources.values())
+            .all(|check| matches!(check.status, Status::Ok));
+
+        Readiness {
+            status: match ready {
+                true => Status::Ok,
+                false => Status::Unavailable,
+            },
+            database,
+            migrations,
+            sources,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use tempfile::NamedTempFile;
+use tokio::task;
+use tracing::{error, info};
+
+/// Proportion of the maximum size the cache is reduced to when evicting, so that eviction
+/// doesn't run on every write once the cache is full.
+const EVICT_TO: f64 = 0.9;
+
+/// Processed images stored on disk, keyed by file name. The least recently used images are
+/// removed once the cache grows beyond its maximum size.
+pub struct ImageCache {
+    dir: PathBuf,
+    max_size: u64,
+    /// Total size of the cache, counted when created and kept up to date as images are written.
+    size: AtomicU64,
+    evicting: AtomicBool,
+}
+
+impl ImageCache {
+    pub fn new(dir: PathBuf, max_size: u64) -> Result<Self, io::Error> {
+        fs::create_dir_all(&dir)?;
+        let size = entries(&dir)?.iter().map(|entry| entry.size).sum();
+
+        Ok(Self {
+            dir,
+            max_size,
+            size: AtomicU64::new(size),
+            evicting: AtomicBool::new(false),
+        })
+    }
+
+    /// Reads an image, marking it as recently used.
+    pub async fn read(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.dir.join(key);
+
+        task::spawn_blocking(move || {
+            let bytes = fs::read(&path).ok()?;
+            // Failing to update the time only affects the order of eviction
+            let _ = File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+
+            Some(bytes)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Writes an image, evicting others if the cache is too large. Images are written to a
+    /// uniquely named temporary file first, so that partial images are never read.
+    pub async fn write(self: &Arc<Self>, key: &str, bytes: Vec<u8>) {
+        let cache = Arc::clone(self);
+        let path = self.dir.join(key);
+
+        let result = task::spawn_blocking(move || {
+            let mut temporary = NamedTempFile::new_in(&cache.dir)?;
+            temporary.write_all(&bytes)?;
+            // An image written again replaces the existing copy, which is no longer counted
+            let replaced = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+            temporary.persist(&path)?;
+
+            let added = bytes.len() as u64;
+            let resize = |size: u64| (size + added).saturating_sub(replaced);
+            let size = cache
+                .size
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |size| {
+                    Some(resize(size))
+                })
+                .unwrap_or_else(|size| size);
+            if resize(size) > cache.max_size {
+                cache.evict()?;
+            }
+
+            Ok::<_, io::Error>(())
+        })
+        .await;
+
+        if let Ok(Err(e)) = result {
+            error!(message = "unable to store image", error = ?e);
+        }
+    }
+
+    /// Removes the least recently used images until the cache is below its maximum size.
+    fn evict(&self) -> Result<(), io::Error> {
+        // Only one write evicts at a time, as the others would remove the same images
+        if self.evicting.swap(true, Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let result = self.remove_least_recently_used();
+        self.evicting.store(false, Ordering::Release);
+        result
+    }
+
+    fn remove_least_recently_used(&self) -> Result<(), io::Error> {
+        let mut entries = entries(&self.dir)?;
+        entries.sort_by_key(|entry| entry.modified);
+
+        let target = (self.max_size as f64 * EVICT_TO) as u64;
+        let mut size = entries.iter().map(|entry| entry.size).sum::<u64>();
+        let mut evicted = 0;
+        for entry in entries {
+            if size <= target {
+                break;
+            }
+            // Images may have been removed or replaced since they were listed
+            if fs::remove_file(&entry.path).is_ok() {
+                size -= entry.size;
+                evicted += 1;
+            }
+        }
+
+        self.size.store(size, Ordering::Relaxed);
+        info!(message = "evicted images", count = evicted, size);
+
+        Ok(())
+    }
+}
+
+struct Entry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Lists the files in the cache, including any temporary files left behind.
+fn entries(dir: &Path) -> Result<Vec<Entry>, io::Error> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())?;
+
+            Some(Entry {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,155 @@
+mod cache;
+mod proxy;
+mod resolve;
+mod rewrite;
+
+use std::path::PathBuf;
+
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::Sha256;
+use shared::config::Section;
+
+pub use proxy::{ImageParams, ImageProxy, OutputFormat};
+
+const DEFAULT_CACHE_DIR: &str = "image-cache";
+/// Size the cache is kept below, in megabytes.
+const DEFAULT_MAX_CACHE_SIZE: u64 = 1024;
+const DEFAULT_AUTHENTICATED_HOST: &str = "raw.githubusercontent.com";
+
+pub struct ImageConfig {
+    urls: ImageUrls,
+    cache_dir: PathBuf,
+    /// Size the cache is kept below, in bytes.
+    max_cache_size: u64,
+    /// Whether image URLs in plugin output are rewritten to go through the proxy.
+    rewrite: bool,
+    /// Hosts that are sent the token of the user an image was signed for, so that images in
+    /// private repositories can be shown to visitors.
+    authenticated_hosts: Vec<String>,
+}
+
+impl ImageConfig {
+    /// Reads the `image` section: `signing_key`, `cache_dir`, `max_cache_size` (megabytes),
+    /// `rewrite` and `authenticated_hosts`. Returns `None` if no signing key is set, as the proxy
+    /// is disabled, or without an API root, which has already been reported. Every key is read
+    /// regardless, so that none are reported as unrecognised.
+    pub fn from_config(section: &Section, api_root: Option<&Url>) -> Option<Self> {
+        let key = section.optional::<String>("signing_key");
+        let cache_dir = section
+            .optional("cache_dir")
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR));
+        let max_cache_size = section
+            .optional::<u64>("max_cache_size")
+            .unwrap_or(DEFAULT_MAX_CACHE_SIZE)
+            * 1024
+            * 1024;
+        let rewrite = section.optional("rewrite").unwrap_or_default();
+        let authenticated_hosts = section
+            .optional::<Vec<String>>("authenticated_hosts")
+            .unwrap_or_else(|| vec![DEFAULT_AUTHENTICATED_HOST.to_string()]);
+        let base = api_root?.join("img").ok()?;
+
+        Some(Self {
+            urls: ImageUrls {
+                key: key?.into_bytes(),
+                base,
+            },
+            cache_dir,
+            max_cache_size,
+            rewrite,
+            authenticated_hosts: authenticated_hosts
+                .into_iter()
+                .map(|host| host.trim().to_lowercase())
+                .collect(),
+        })
+    }
+
+    /// URLs used to rewrite plugin output, if enabled.
+    pub fn rewrite_urls(&self) -> Option<ImageUrls> {
+        self.rewrite.then(|| self.urls.clone())
+    }
+}
+
+/// Creates and verifies signed proxy URLs, so that the proxy only fetches images this service
+/// has linked to.
+#[derive(Clone)]
+pub struct ImageUrls {
+    key: Vec<u8>,
+    base: Url,
+}
+
+impl ImageUrls {
+    fn mac(
+        &self,
+        url: &str,
+        width: Option<u32>,
+        user: Option<&str>,
+        format: Option<OutputFormat>,
+    ) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(
+            format!(
+                "{url}\n{}\n{}\n{}",
+                width.map(|width| width.to_string()).unwrap_or_default(),
+                user.unwrap_or_default(),
+                format.map(OutputFormat::name).unwrap_or_default()
+            )
+            .as_bytes(),
+        );
+        mac
+    }
+
+    /// Creates a proxy URL for an image, optionally resized to a width and converted to a
+    /// format, which is otherwise negotiated. `user` is the `source/username` whose token is used
+    /// to fetch images from authenticated hosts.
+    pub fn sign(
+        &self,
+        url: &str,
+        width: Option<u32>,
+        user: Option<&str>,
+        format: Option<OutputFormat>,
+    ) -> Url {
+        let signature = hex::encode(self.mac(url, width, user, format).finalize().into_bytes());
+
+        let mut signed = self.base.clone();
+        {
+            let mut query = signed.query_pairs_mut();
+            query.append_pair("url", url);
+            if let Some(width) = width {
+                query.append_pair("w", &width.to_string());
+            }
+            if let Some(user) = user {
+                query.append_pair("user", user);
+            }
+            if let Some(format) = format {
+                query.append_pair("format", format.name());
+            }
+            query.append_pair("sig", &signature);
+        }
+
+        signed
+    }
+
+    pub fn verify(
+        &self,
+        url: &str,
+        width: Option<u32>,
+        user: Option<&str>,
+        format: Option<OutputFormat>,
+        sig: &str,
+    ) -> bool {
+        hex::decode(sig)
+            .map(|sig| {
+                self.mac(url, width, user, format)
+                    .verify_slice(&sig)
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_proxied(&self, url: &str) -> bool {
+        url.starts_with(self.base.as_str())
+    }
+}
@@ -0,0 +1,361 @@
+use std::{io::Cursor, sync::Arc};
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use image::{
+    codecs::{avif::AvifEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageError, ImageReader, Limits,
+};
+use reqwest::{redirect, Client, Url};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::task;
+use tracing::{error, warn};
+
+use crate::{data::DataService, APP_USER_AGENT};
+
+use super::{
+    cache::ImageCache,
+    resolve::{self, GlobalResolver},
+    ImageConfig,
+};
+
+/// Largest width an image can be resized to.
+const MAX_WIDTH: u32 = 2048;
+/// Largest image that is fetched, in bytes.
+const MAX_SOURCE_SIZE: usize = 16 * 1024 * 1024;
+/// Largest width or height of an image that is decoded.
+const MAX_SOURCE_DIMENSION: u32 = 8192;
+/// Most memory that decoding an image can allocate, in bytes.
+const MAX_DECODE_ALLOCATION: u64 = 256 * 1024 * 1024;
+/// Most redirects followed when fetching an image.
+const MAX_REDIRECTS: usize = 5;
+/// AVIF encoder speed, from 1 (slowest) to 10 (fastest).
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Webp,
+    Avif,
+    Png,
+}
+
+impl OutputFormat {
+    /// Name used in proxy URLs.
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Avif => "avif",
+            Self::Png => "png",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Webp => "image/webp",
+            Self::Avif => "image/avif",
+            Self::Png => "image/png",
+        }
+    }
+
+    /// Picks the smallest format the client accepts, falling back to PNG.
+    fn negotiate(headers: &HeaderMap) -> Self {
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .unwrap_or_default();
+
+        [Self::Avif, Self::Webp]
+            .into_iter()
+            .find(|format| accept.contains(format.content_type()))
+            .unwrap_or(Self::Png)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ImageParams {
+    url: String,
+    w: Option<u32>,
+    /// `source/username` whose token is used for authenticated hosts.
+    user: Option<String>,
+    sig: String,
+    /// Chosen from the `Accept` header if not given. Part of the signature, so that clients
+    /// can't request every format of an image.
+    format: Option<OutputFormat>,
+}
+
+/// An image ready to be served, as stored in the cache.
+struct Image {
+    content_type: String,
+    bytes: Vec<u8>,
+}
+
+impl Image {
+    /// Stored as the content type, a newline, then the image.
+    fn from_cached(cached: Vec<u8>) -> Option<Self> {
+        let split = cached.iter().position(|&byte| byte == b'\n')?;
+
+        Some(Self {
+            content_type: String::from_utf8(cached[..split].to_vec()).ok()?,
+            bytes: cached[split + 1..].to_vec(),
+        })
+    }
+
+    fn to_cached(&self) -> Vec<u8> {
+        [self.content_type.as_bytes(), b"\n", &self.bytes].concat()
+    }
+}
+
+/// Fetches images, resizing and re-encoding them before storing them on disk. Only hosts on the
+/// public internet are fetched from, including when following redirects.
+pub struct ImageProxy {
+    config: ImageConfig,
+    data: Arc<DataService>,
+    client: Client,
+    cache: Arc<ImageCache>,
+}
+
+impl ImageProxy {
+    pub fn new(config: ImageConfig, data: &Arc<DataService>) -> Result<Self, std::io::Error> {
+        let cache = ImageCache::new(config.cache_dir.clone(), config.max_cache_size)?;
+
+        Ok(Self {
+            config,
+            data: Arc::clone(data),
+            client: Client::builder()
+                .user_agent(APP_USER_AGENT)
+                .dns_resolver(Arc::new(GlobalResolver))
+                // A proxy would resolve hosts itself, bypassing the resolver
+                .no_proxy()
+                .redirect(redirect::Policy::custom(|attempt| {
+                    if attempt.previous().len() >= MAX_REDIRECTS {
+                        attempt.error("too many redirects")
+                    } else if resolve::is_allowed(attempt.url()) {
+                        attempt.follow()
+                    } else {
+                        attempt.error("redirected to a disallowed URL")
+                    }
+                }))
+                .build()
+                .expect("client configuration is valid"),
+            cache: Arc::new(cache),
+        })
+    }
+
+    pub async fn get(&self, params: ImageParams, headers: &HeaderMap) -> Response {
+        if !self.config.urls.verify(
+            &params.url,
+            params.w,
+            params.user.as_deref(),
+            params.format,
+            &params.sig,
+        ) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        if params
+            .w
+            .is_some_and(|width| width == 0 || width > MAX_WIDTH)
+        {
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+
+        let format = params
+            .format
+            .unwrap_or_else(|| OutputFormat::negotiate(headers));
+
+        let image = match self.cached(&params, format).await {
+            Ok(image) => image,
+            Err(response) => return response,
+        };
+
+        let mut response = (
+            [(
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=86400"),
+            )],
+            image.bytes,
+        )
+            .into_response();
+        if let Ok(content_type) = HeaderValue::from_str(&image.content_type) {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, content_type);
+        }
+        if params.format.is_none() {
+            response
+                .headers_mut()
+                .insert(header::VARY, HeaderValue::from_static("accept"));
+        }
+        // SVGs are passed through, so must not be able to run scripts
+        response.headers_mut().insert(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static("default-src 'none'; style-src 'unsafe-inline'; sandbox"),
+        );
+
+        response
+    }
+
+    fn cache_key(params: &ImageParams, format: OutputFormat) -> String {
+        hex::encode(Sha256::digest(format!(
+            "{}\n{}\n{}\n{}",
+            params.url,
+            params.w.unwrap_or_default(),
+            params.user.as_deref().unwrap_or_default(),
+            format.content_type()
+        )))
+    }
+
+    async fn cached(&self, params: &ImageParams, format: OutputFormat) -> Result<Image, Response> {
+        let key = Self::cache_key(params, format);
+        if let Some(image) = self.cache.read(&key).await.and_then(Image::from_cached) {
+            return Ok(image);
+        }
+
+        let (content_type, source) = self.fetch(params).await?;
+        let width = params.w;
+        let image = task::spawn_blocking(move || process(content_type, source, width, format))
+            .await
+            .unwrap_or(Err(StatusCode::INTERNAL_SERVER_ERROR))
+            .map_err(IntoResponse::into_response)?;
+
+        self.cache.write(&key, image.to_cached()).await;
+
+        Ok(image)
+    }
+
+    async fn token(&self, user: &str) -> Option<String> {
+        let (source_identifier, username) = user.split_once('/')?;
+
+        self.data
+            .user_sources(username)
+            .await
+            .ok()?
+            .into_iter()
+            .find(|user_source| user_source.site == source_identifier)
+            .map(|user_source| user_source.token)
+    }
+
+    async fn fetch(&self, params: &ImageParams) -> Result<(String, Vec<u8>), Response> {
+        let url: Url = params
+            .url
+            .parse()
+            .map_err(|_| StatusCode::BAD_REQUEST.into_response())?;
+        if !resolve::is_allowed(&url) {
+            return Err(StatusCode::BAD_REQUEST.into_response());
+        }
+
+        let mut request = self.client.get(url.clone());
+        let authenticated = url.host_str().is_some_and(|host| {
+            self.config
+                .authenticated_hosts
+                .iter()
+                .any(|authenticated| authenticated.eq_ignore_ascii_case(host))
+        });
+        let token = match &params.user {
+            Some(user) if authenticated => self.token(user).await,
+            _ => None,
+        };
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        let mut response = request.send().await.map_err(|e| {
+            warn!(message = "unable to fetch image", url = %url, error = ?e);
+            StatusCode::BAD_GATEWAY.into_response()
+        })?;
+        match response.status() {
+            status if status.is_success() => {}
+            StatusCode::NOT_FOUND => return Err(StatusCode::NOT_FOUND.into_response()),
+            _ => return Err(StatusCode::BAD_GATEWAY.into_response()),
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_SOURCE_SIZE as u64)
+        {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+        }
+
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !content_type.starts_with("image/") {
+            return Err(StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response());
+        }
+
+        let mut source = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|_| StatusCode::BAD_GATEWAY.into_response())?
+        {
+            if source.len() + chunk.len() > MAX_SOURCE_SIZE {
+                return Err(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+            }
+            source.extend_from_slice(&chunk);
+        }
+
+        Ok((content_type, source))
+    }
+}
+
+/// Resizes and re-encodes an image. SVGs and GIFs are passed through unchanged, as they can't be
+/// rasterised or would lose their animation.
+fn process(
+    content_type: String,
+    source: Vec<u8>,
+    width: Option<u32>,
+    format: OutputFormat,
+) -> Result<Image, StatusCode> {
+    if content_type.starts_with("image/svg+xml") || content_type.starts_with("image/gif") {
+        return Ok(Image {
+            content_type,
+            bytes: source,
+        });
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOCATION);
+
+    let mut reader = ImageReader::new(Cursor::new(&source))
+        .with_guessed_format()
+        .map_err(|_| StatusCode::UNSUPPORTED_MEDIA_TYPE)?;
+    reader.limits(limits);
+    let mut image = reader.decode().map_err(|e| match e {
+        ImageError::Limits(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+    })?;
+    if let Some(width) = width.filter(|&width| width < image.width()) {
+        image = image.resize(width, u32::MAX, FilterType::Lanczos3);
+    }
+    let image = DynamicImage::ImageRgba8(image.to_rgba8());
+
+    let mut bytes = Cursor::new(Vec::new());
+    match format {
+        OutputFormat::Webp => image.write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+        OutputFormat::Avif => image.write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut bytes,
+            AVIF_SPEED,
+            AVIF_QUALITY,
+        )),
+        OutputFormat::Png => image.write_with_encoder(PngEncoder::new(&mut bytes)),
+    }
+    .map_err(|e| {
+        error!(message = "unable to encode image", error = ?e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Image {
+        content_type: format.content_type().to_string(),
+        bytes: bytes.into_inner(),
+    })
+}
@@ -0,0 +1,97 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    Url,
+};
+use tokio::net;
+use url::Host;
+
+/// Whether an address is reachable on the public internet. Private, loopback, link-local and
+/// other special-purpose ranges are excluded, so that the proxy can't be used to reach services
+/// on the host or its network.
+pub fn is_global(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_global_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_global_v4(ip),
+            None => is_global_v6(ip),
+        },
+    }
+}
+
+fn is_global_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network"
+        || a == 0
+        // Shared address space
+        || (a == 100 && (64..128).contains(&b))
+        // Protocol assignments
+        || (a == 192 && b == 0 && ip.octets()[2] == 0)
+        // Benchmarking
+        || (a == 198 && (18..20).contains(&b))
+        // Reserved
+        || a >= 240)
+}
+
+fn is_global_v6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local
+        || (first & 0xfe00) == 0xfc00
+        // Link-local
+        || (first & 0xffc0) == 0xfe80
+        // Documentation
+        || (first == 0x2001 && second == 0x0db8)
+        // IPv4-compatible and other reserved addresses
+        || first == 0)
+}
+
+/// Whether a URL can be fetched by the proxy. Hosts given by name are checked when resolved, by
+/// [`GlobalResolver`].
+pub fn is_allowed(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+
+    match url.host() {
+        Some(Host::Domain(_)) => true,
+        Some(Host::Ipv4(ip)) => is_global(ip.into()),
+        Some(Host::Ipv6(ip)) => is_global(ip.into()),
+        None => false,
+    }
+}
+
+/// Resolves hosts with the system resolver, discarding addresses that aren't global. Checking
+/// addresses as they are connected to, rather than beforehand, means a host can't resolve to a
+/// different address by the time it is fetched.
+pub struct GlobalResolver;
+
+impl Resolve for GlobalResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_global(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+
+            if addrs.is_empty() {
+                return Err(format!("`{}` has no public addresses", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use lol_html::{element, rewrite_str, RewriteStrSettings};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use shared::plugin::{
+    BlurbFormat, BlurbResponse, PluginResponse, PostsResponse, ProjectDetailResponse, UserResponse,
+};
+use tracing::warn;
+
+use super::ImageUrls;
+
+impl ImageUrls {
+    /// Proxies an absolute image URL, leaving relative, data and already proxied URLs alone.
+    fn proxied(&self, url: &str, user: &str) -> Option<String> {
+        (url.starts_with("https://") || url.starts_with("http://"))
+            .then_some(url)
+            .filter(|url| !self.is_proxied(url))
+            .map(|url| self.sign(url, None, Some(user), None).to_string())
+    }
+
+    fn rewrite_url(&self, url: &mut String, user: &str) {
+        if let Some(proxied) = self.proxied(url, user) {
+            *url = proxied;
+        }
+    }
+
+    /// Rewrites the sources of `img` elements.
+    fn rewrite_html(&self, html: &str, user: &str) -> String {
+        rewrite_str(
+            html,
+            RewriteStrSettings {
+                element_content_handlers: vec![element!("img[src]", |img| {
+                    if let Some(proxied) = img
+                        .get_attribute("src")
+                        .and_then(|src| self.proxied(&src, user))
+                    {
+                        img.set_attribute("src", &proxied)?;
+                    }
+
+                    Ok(())
+                })],
+                ..RewriteStrSettings::new()
+            },
+        )
+        .unwrap_or_else(|e| {
+            warn!(message = "unable to rewrite image URLs", error = ?e);
+            html.to_string()
+        })
+    }
+
+    /// Rewrites Markdown images, and `img` elements in inline HTML. Reference-style images are
+    /// left alone, as their destinations aren't next to them.
+    fn rewrite_markdown(&self, markdown: &str, user: &str) -> String {
+        let mut replacements = Vec::new();
+        for (event, range) in Parser::new_ext(markdown, Options::all()).into_offset_iter() {
+            match event {
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    let Some(proxied) = self.proxied(&dest_url, user) else {
+                        continue;
+                    };
+                    if let Some(start) = markdown[range.clone()].find(dest_url.as_ref()) {
+                        let start = range.start + start;
+                        replacements.push((start..start + dest_url.len(), proxied));
+                    }
+                }
+                Event::Html(html) | Event::InlineHtml(html) => {
+                    let rewritten = self.rewrite_html(&html, user);
+                    if rewritten != html.as_ref() && markdown[range.clone()] == *html {
+                        replacements.push((range, rewritten));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut rewritten = markdown.to_string();
+        for (range, replacement) in replacements.into_iter().rev() {
+            rewritten.replace_range(range, &replacement);
+        }
+
+        rewritten
+    }
+
+    /// Points image URLs in the output of a plugin at the proxy. `user` is the `source/username`
+    /// the output belongs to.
+    pub fn rewrite(&self, response: &mut PluginResponse, user: &str) {
+        if let Some(profile) = response.downcast_mut::<UserResponse>() {
+            self.rewrite_url(&mut profile.avatar, user);
+        } else if let Some(posts) = response.downcast_mut::<PostsResponse>() {
+            for post in posts {
+                if let Some(cover_image) = &mut post.cover_image {
+                    self.rewrite_url(cover_image, user);
+                }
+                post.body = self.rewrite_markdown(&post.body, user);
+            }
+        } else if let Some(detail) = response.downcast_mut::<ProjectDetailResponse>() {
+            if let Some(readme) = &detail.readme {
+                detail.readme = Some(self.rewrite_html(readme, user));
+            }
+        } else if let Some(blurb) = response.downcast_mut::<BlurbResponse>() {
+            match blurb.format {
+                BlurbFormat::Html => blurb.blurb = self.rewrite_html(&blurb.blurb, user),
+                BlurbFormat::Markdown => blurb.blurb = self.rewrite_markdown(&blurb.blurb, user),
+                // Plain text has no images
+                BlurbFormat::Text => {}
+            }
+        }
+    }
+}
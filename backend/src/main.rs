@@ -1,43 +1,81 @@
-use std::{collections::HashMap, sync::Arc};
-
-use axum::extract::Path;
-use axum::http::HeaderValue;
-use axum::response::IntoResponse;
-use axum::{routing::get, Router, Server};
-use reqwest::{header, Method, StatusCode, Url};
-use sea_orm::{ActiveModelTrait, ConnectOptions, Database, DbErr, EntityTrait, Set};
+mod config;
+mod data;
+mod health;
+mod images;
+mod privacy;
+mod scheduler;
+mod search;
+mod server;
+mod templates;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::{Html, IntoResponse};
+use axum::{routing::get, Json, Router};
+use reqwest::{header, Method, StatusCode};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ConnectOptions, Database, DatabaseConnection, DbErr,
+    EntityTrait, Set,
+};
 use serde::Deserialize;
-use shared::environment::Environment;
-use shared::plugin::{AuthTokenPayload, PluginIdentifier, SourceError};
+use shared::config::ConfigError;
+use shared::plugin::{
+    AuthTokenPayload, InvalidationPayload, Plugin, PluginIdentifier, RequestTypes,
+};
 use shared::source::{Source, SourceIdentifier};
 use thiserror::Error;
-use tokio::{sync::mpsc::unbounded_channel, task};
+use tokio::{sync::mpsc::unbounded_channel, task, time};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tower_http::LatencyUnit;
-use tracing::{error, info, info_span, Level};
+use tracing::{error, info, info_span, warn, Level};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use url::ParseError;
 
-use entities::{user, user_source, UserSource};
+use config::{AppConfig, Args};
+use data::{DataService, MAX_AGE};
+use entities::{user, user_source};
 use github::Github;
+use health::Health;
+use images::{ImageConfig, ImageParams, ImageProxy};
+use privacy::PrivacyPolicy;
+use scheduler::Scheduler;
+use search::{Search, SearchParams};
+use server::shutdown_signal;
+use templates::{prefers_html, TemplateError, Templates};
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 #[derive(Debug, Error)]
-enum BackendError {
+pub enum BackendError {
     #[error("loading env file with dotenvy failed: {0}")]
     DotEnv(#[from] dotenvy::Error),
-    #[error("source error: {0}")]
-    Source(#[from] SourceError),
     #[error("DB error: {0}")]
     Db(#[from] DbErr),
-    #[error("missing environment variable: {0}")]
-    Environment(String),
+    #[error("{0}")]
+    Arguments(String),
+    #[error("{0}")]
+    Config(#[from] ConfigError),
     #[error("unable to parse URL: {0}")]
     UrlParseError(#[from] ParseError),
+    #[error("unable to load templates: {0}")]
+    Template(#[from] TemplateError),
+    #[error("unable to create image cache: {0}")]
+    ImageCache(std::io::Error),
+    #[error("unable to start server: {0}")]
+    Listen(#[from] std::io::Error),
+    #[error("server error: {0}")]
+    Server(#[from] hyper::Error),
 }
 
+type Plugins = HashMap<(String, SourceIdentifier, PluginIdentifier), Plugin>;
+
 #[derive(Deserialize)]
 struct PluginPathParams {
     request_type: String,
@@ -46,8 +84,90 @@ struct PluginPathParams {
     username: String,
 }
 
+#[derive(Deserialize)]
+struct UserSourcePathParams {
+    source_identifier: String,
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct PagePathParams {
+    username: String,
+    path: Option<String>,
+}
+
+/// Responses to preview requests contain unpublished content, so must not be cached publicly.
+/// Other responses can be cached until their data is due to change.
+fn cache_control(preview: bool, max_age: Duration) -> HeaderValue {
+    if preview {
+        HeaderValue::from_static("private, no-store")
+    } else {
+        HeaderValue::from_str(&format!("public, max-age={}", max_age.as_secs()))
+            .expect("cache control is a valid header value")
+    }
+}
+
+/// Extracts the token from an `Authorization: Bearer` header.
+/// Saves the tokens from a login. A user logging in again keeps their user and settings, but
+/// their tokens are replaced by those they were just shown.
+async fn store_auth_token(
+    db: &DatabaseConnection,
+    auth_token: AuthTokenPayload,
+) -> Result<(), DbErr> {
+    let existing =
+        user_source::Entity::find_by_id((auth_token.username.clone(), auth_token.source.clone()))
+            .one(db)
+            .await?;
+    let user_id = match existing {
+        Some(user_source) => user_source.user_id,
+        None => {
+            let user = user::ActiveModel {
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            info!("user created in DB");
+            user.id
+        }
+    };
+
+    let user_source = user_source::ActiveModel {
+        user_id: Set(user_id),
+        site: Set(auth_token.source),
+        username: Set(auth_token.username),
+        token: Set(auth_token.auth_token),
+        preview_token: Set(auth_token.preview_token),
+        settings_token: Set(auth_token.settings_token),
+        ..Default::default()
+    };
+    user_source::Entity::insert(user_source)
+        .on_conflict(
+            OnConflict::columns([user_source::Column::Username, user_source::Column::Site])
+                .update_columns([
+                    user_source::Column::Token,
+                    user_source::Column::PreviewToken,
+                    user_source::Column::SettingsToken,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
 #[tokio::main]
 async fn main() -> Result<(), BackendError> {
+    let args = Args::parse().map_err(BackendError::Arguments)?;
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
@@ -67,20 +187,17 @@ async fn main() -> Result<(), BackendError> {
         dotenvy::from_filename("../.env.dev")?;
     }
 
-    let environment = {
-        let mut environment = std::env::vars().collect::<Environment>();
-        environment.extend([("USER_AGENT".to_string(), APP_USER_AGENT.to_string())].into_iter());
-        environment
-    };
-    info!("Loaded environment");
+    let config = AppConfig::load(&args).inspect_err(|e| error!("{e}"))?;
+    info!("Loaded configuration");
+    if args.check_config {
+        println!("Configuration is valid");
+        return Ok(());
+    }
 
-    let api_root: Url = environment
-        .get("API_ROOT")
-        .ok_or(BackendError::Environment("API_ROOT".to_string()))?
-        .parse()?;
+    let api_root = config.api_root;
     info!("Running at {api_root}");
 
-    let mut opt = ConnectOptions::new(environment.get("DATABASE_URL").unwrap().clone());
+    let mut opt = ConnectOptions::new(config.database_url);
     opt.max_connections(100)
         .min_connections(5)
         .sqlx_logging(true);
@@ -88,122 +205,334 @@ async fn main() -> Result<(), BackendError> {
     info!("Connected to database");
 
     let (save_auth_token, mut save_auth_token_rx) = unbounded_channel::<AuthTokenPayload>();
+    let (invalidate_data, mut invalidate_data_rx) = unbounded_channel::<InvalidationPayload>();
 
-    let (auth_plugins, plugins) = [Github::from_environment(&environment)?].into_iter().fold(
-        (Vec::new(), HashMap::new()),
-        |(mut auth_plugins, mut plugins), source| {
-            auth_plugins.extend(
-                source
-                    .get_auth_plugins()
-                    .into_iter()
-                    .map(|plugin| (source.get_identifier(), plugin.get_identifier(), plugin)),
+    let (auth_plugins, webhook_plugins, plugins, health_checks) =
+        [Github::new(config.github, APP_USER_AGENT)]
+            .into_iter()
+            .fold(
+                (Vec::new(), Vec::new(), HashMap::new(), Vec::new()),
+                |(mut auth_plugins, mut webhook_plugins, mut plugins, mut health_checks),
+                 source| {
+                    auth_plugins.extend(
+                        source.get_auth_plugins().into_iter().map(|plugin| {
+                            (source.get_identifier(), plugin.get_identifier(), plugin)
+                        }),
+                    );
+                    webhook_plugins.extend(
+                        source.get_webhook_plugins().into_iter().map(|plugin| {
+                            (source.get_identifier(), plugin.get_identifier(), plugin)
+                        }),
+                    );
+                    plugins.extend(source.get_plugins().into_iter().map(|plugin| {
+                        (
+                            (
+                                plugin.request_type(),
+                                source.get_identifier(),
+                                plugin.get_identifier(),
+                            ),
+                            plugin,
+                        )
+                    }));
+                    health_checks.push((source.get_identifier(), source.get_health_check()));
+
+                    (auth_plugins, webhook_plugins, plugins, health_checks)
+                },
             );
-            plugins.extend(source.get_plugins().into_iter().map(|plugin| {
-                (
-                    (
-                        plugin.request_type(),
-                        source.get_identifier(),
-                        plugin.get_identifier(),
-                    ),
-                    plugin,
-                )
-            }));
-
-            (auth_plugins, plugins)
-        },
-    );
     info!("Loaded plugins");
 
-    {
+    let request_types = Arc::new(plugins.values().fold(
+        RequestTypes::default(),
+        |mut request_types, plugin| {
+            request_types.register_plugin(plugin);
+            request_types
+        },
+    ));
+
+    let data = Arc::new(DataService::new(
+        &db,
+        plugins,
+        config.images.as_ref().and_then(ImageConfig::rewrite_urls),
+    ));
+
+    let save_auth_tokens = {
         let db = db.clone();
         task::spawn(async move {
             while let Some(auth_token) = save_auth_token_rx.recv().await {
                 let span = info_span!("save token", source = auth_token.source);
                 let _guard = span.enter();
 
-                // Create user in DB
-                let user = user::ActiveModel {
-                    ..Default::default()
-                };
-
-                match user.insert(db.as_ref()).await {
-                    Ok(user) => {
-                        info!("user created in DB");
-
-                        // Add to user source
-                        let user_source = user_source::ActiveModel {
-                            user_id: Set(user.id),
-                            site: Set(auth_token.source),
-                            username: Set(auth_token.username),
-                            token: Set(auth_token.auth_token),
-                            ..Default::default()
-                        };
-
-                        match user_source.insert(db.as_ref()).await {
-                            Ok(_) => {
-                                info!("token saved in Db");
-                            }
-                            Err(e) => {
-                                error!(message = "unable to save auth token to DB", error = ?e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!(message = "unable to create user in DB", error = ?e);
-                    }
+                match store_auth_token(db.as_ref(), auth_token).await {
+                    Ok(()) => info!("token saved in Db"),
+                    Err(e) => error!(message = "unable to save auth token to DB", error = ?e),
                 }
             }
+        })
+    };
+
+    let templates = config
+        .template_dir
+        .map(|directory| Templates::load(&directory))
+        .transpose()?
+        .map(Arc::new);
+    if templates.is_some() {
+        info!("Loaded templates");
+    }
+
+    {
+        let data = data.clone();
+        task::spawn(async move {
+            while let Some(payload) = invalidate_data_rx.recv().await {
+                data.invalidate(payload).await;
+            }
         });
     }
 
-    let plugins = Arc::new(plugins);
+    match config.scheduler {
+        Some(config) => {
+            Scheduler::new(&data, config).spawn();
+            info!("Started refresh scheduler");
+        }
+        None => info!("Refresh scheduler disabled"),
+    }
+
+    let search = Arc::new(Search::new(&data));
+    let health = Arc::new(Health::new(&db, health_checks));
+
+    let images = config
+        .images
+        .map(|config| ImageProxy::new(config, &data))
+        .transpose()
+        .map_err(BackendError::ImageCache)?
+        .map(Arc::new);
+    match images {
+        Some(_) => info!("Started image proxy"),
+        None => info!("Image proxy disabled"),
+    }
 
-    let router = Router::new()
+    let mut router = Router::new()
         .route("/", get(|| async { "Hello, World!" }))
+        // The process is running, whether or not its dependencies are available
         .route(
-            "/api/:request_type/:source_identifier/:plugin_identifier/:username",
+            "/healthz",
+            get(|| async { Json(serde_json::json!({ "status": "ok" })) }),
+        )
+        .route("/readyz", get(|| async move { health.readiness().await }))
+        .route(
+            "/api/schema",
+            get({
+                let request_types = request_types.clone();
+
+                || async move {
+                    Json(
+                        request_types
+                            .names()
+                            .map(|name| (name, request_types.schema(name)))
+                            .collect::<BTreeMap<_, _>>(),
+                    )
+                }
+            }),
+        )
+        .route(
+            "/api/schema/:request_type",
+            get(|Path(request_type): Path<String>| async move {
+                request_types
+                    .schema(&request_type)
+                    .map(Json)
+                    .ok_or(StatusCode::NOT_FOUND)
+            }),
+        )
+        .route(
+            "/api/privacy/:source_identifier/:username",
+            // Settings are authorised by the user's settings token
             get({
-                let db = db.clone();
-                let plugins = plugins.clone();
-
-                |Path(params): Path<PluginPathParams>| async move {
-                    // Attempt to find authentication for the user and source
-                    let user_source =
-                        UserSource::find_by_id((params.username, params.source_identifier.clone()))
-                            .one(db.as_ref())
-                            .await
-                            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
-                            .and_then(|user_source| user_source.ok_or(StatusCode::UNAUTHORIZED));
-
-                    // Attempt to find plugin to run
-                    let plugin = plugins
-                        .get(&(
-                            params.request_type.to_string(),
-                            SourceIdentifier::new(&params.source_identifier),
-                            PluginIdentifier::new(&params.plugin_identifier),
-                        ))
-                        .ok_or(StatusCode::NOT_FOUND);
-
-                    // If user and plugin found, run it
-                    match (user_source, plugin) {
-                        (Ok(user_source), Ok(plugin)) => {
-                            let mut response = plugin
-                                .get_data(&user_source.username, &user_source.token)
-                                .await
-                                .into_response();
-
-                            response.headers_mut().extend([(
+                let data = data.clone();
+
+                |Path(params): Path<UserSourcePathParams>, headers: HeaderMap| async move {
+                    let token =
+                        bearer_token(&headers).ok_or(StatusCode::UNAUTHORIZED.into_response())?;
+
+                    data.privacy_policy(&params.username, &params.source_identifier, token)
+                        .await
+                        .map(Json)
+                }
+            })
+            .put({
+                let data = data.clone();
+
+                |Path(params): Path<UserSourcePathParams>,
+                 headers: HeaderMap,
+                 Json(policy): Json<PrivacyPolicy>| async move {
+                    let token =
+                        bearer_token(&headers).ok_or(StatusCode::UNAUTHORIZED.into_response())?;
+
+                    data.set_privacy_policy(
+                        &params.username,
+                        &params.source_identifier,
+                        token,
+                        &policy,
+                    )
+                    .await
+                    .map(|_| StatusCode::NO_CONTENT)
+                }
+            }),
+        )
+        .route(
+            "/api/search/:username",
+            get(
+                |Path(username): Path<String>, Query(params): Query<SearchParams>| async move {
+                    match search.search(&username, params).await {
+                        Ok(results) => (
+                            [(
                                 header::CACHE_CONTROL,
-                                HeaderValue::from_str("public, max-age=86400").unwrap(),
-                            )]);
+                                HeaderValue::from_static("public, max-age=300"),
+                            )],
+                            Json(results),
+                        )
+                            .into_response(),
+                        Err(response) => response,
+                    }
+                },
+            ),
+        )
+        .route(
+            "/api/:request_type/:source_identifier/:plugin_identifier/:username",
+            get({
+                let data = data.clone();
+                let templates = templates.clone();
+
+                |Path(params): Path<PluginPathParams>,
+                 Query(query): Query<HashMap<String, String>>,
+                 headers: HeaderMap| async move {
+                    // Unpublished content is previewed with the user's preview token
+                    let preview_token = bearer_token(&headers);
+                    let (data, max_age) = match data
+                        .get(
+                            &params.request_type,
+                            &params.source_identifier,
+                            &params.plugin_identifier,
+                            &params.username,
+                            query,
+                            preview_token,
+                        )
+                        .await
+                    {
+                        Ok(fetched) => (fetched.response, fetched.max_age),
+                        Err(response) => return response,
+                    };
 
-                            response
+                    // Render a HTML fragment if one is preferred and available
+                    let fragment = templates
+                        .as_ref()
+                        .filter(|_| prefers_html(&headers))
+                        .and_then(|templates| {
+                            templates.render_fragment(
+                                &params.request_type,
+                                &params.source_identifier,
+                                &params.plugin_identifier,
+                                &params.username,
+                                &data,
+                            )
+                        });
+
+                    let mut response = match fragment {
+                        Some(Ok(html)) => Html(html).into_response(),
+                        Some(Err(e)) => {
+                            error!(message = "unable to render fragment", error = ?e);
+                            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
                         }
-                        (Err(e), _) | (_, Err(e)) => e.into_response(),
-                    }
+                        None => Json(data.as_ref()).into_response(),
+                    };
+
+                    response.headers_mut().extend([
+                        (
+                            header::CACHE_CONTROL,
+                            cache_control(preview_token.is_some(), max_age),
+                        ),
+                        (
+                            header::VARY,
+                            HeaderValue::from_static("accept, authorization"),
+                        ),
+                    ]);
+
+                    response
                 }
             }),
-        )
+        );
+
+    if let Some(images) = images {
+        router = router.route(
+            "/img",
+            get(
+                |Query(params): Query<ImageParams>, headers: HeaderMap| async move {
+                    images.get(params, &headers).await
+                },
+            ),
+        );
+    }
+
+    if let Some(templates) = templates {
+        let handle_page = {
+            let data = data.clone();
+
+            move |Path(params): Path<PagePathParams>,
+                  Query(query): Query<HashMap<String, String>>,
+                  headers: HeaderMap| async move {
+                let preview_token = bearer_token(&headers);
+                let path = format!("/{}", params.path.unwrap_or_default());
+                let Some(route) = templates.get_route(&path) else {
+                    return StatusCode::NOT_FOUND.into_response();
+                };
+
+                let mut context = HashMap::new();
+                let mut max_age = MAX_AGE;
+                for (key, reference) in &route.data {
+                    match data
+                        .get(
+                            &reference.request_type,
+                            &reference.source_identifier,
+                            &reference.plugin_identifier,
+                            &params.username,
+                            query.clone(),
+                            preview_token,
+                        )
+                        .await
+                    {
+                        Ok(fetched) => {
+                            // The page changes when any of its data does
+                            max_age = max_age.min(fetched.max_age);
+                            context.insert(key.clone(), fetched.response)
+                        }
+                        Err(response) => return response,
+                    };
+                }
+
+                match templates.render_page(route, &params.username, &path, context) {
+                    Ok(html) => (
+                        [
+                            (
+                                header::CACHE_CONTROL,
+                                cache_control(preview_token.is_some(), max_age),
+                            ),
+                            (header::VARY, HeaderValue::from_static("authorization")),
+                        ],
+                        Html(html),
+                    )
+                        .into_response(),
+                    Err(e) => {
+                        error!(message = "unable to render page", error = ?e);
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                }
+            }
+        };
+
+        router = router
+            .route("/pages/:username", get(handle_page.clone()))
+            .route("/pages/:username/*path", get(handle_page));
+    }
+
+    let router = router
         .nest("/auth", {
             let auth_base = api_root.join("auth/")?;
 
@@ -226,6 +555,18 @@ async fn main() -> Result<(), BackendError> {
                 },
             )?
         })
+        .nest(
+            "/webhook",
+            webhook_plugins.into_iter().fold(
+                Router::new(),
+                |router, (source_identifier, plugin_identifier, plugin)| {
+                    router.nest(
+                        &format!("/{source_identifier}/{plugin_identifier}"),
+                        plugin.register_routes(&source_identifier, invalidate_data.clone()),
+                    )
+                },
+            ),
+        )
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new())
@@ -239,14 +580,28 @@ async fn main() -> Result<(), BackendError> {
         .layer(
             CorsLayer::new()
                 .allow_methods([Method::GET])
+                .allow_headers([header::AUTHORIZATION])
                 .allow_origin(Any),
         );
 
-    info!("Starting server on port 3000");
-    Server::bind(&"0.0.0.0:3000".parse().unwrap())
-        .serve(router.into_make_service())
-        .await
-        .unwrap();
+    // Only the routes keep the channel open from now on, so it closes once the server stops
+    drop(save_auth_token);
+
+    let shutdown_timeout = config.server.shutdown_timeout();
+    server::serve(router, config.server, shutdown_signal()).await?;
+
+    // Save tokens received before shutdown before closing the database. Requests that outlived
+    // the shutdown timeout keep the channel open, so saving is given the same time again
+    match time::timeout(shutdown_timeout, save_auth_tokens).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!(message = "unable to save remaining auth tokens", error = ?e),
+        Err(_) => warn!("stopped waiting for auth tokens to be saved"),
+    }
+    // Connections are shared between clones, so closing one closes the pool
+    if let Err(e) = db.as_ref().clone().close().await {
+        error!(message = "unable to close database connection", error = ?e);
+    }
+    info!("Stopped");
 
     Ok(())
 }
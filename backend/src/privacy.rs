@@ -0,0 +1,74 @@
+use entities::user_source;
+use sea_orm::JsonValue;
+use serde::{Deserialize, Serialize};
+use shared::plugin::{PluginError, PluginResponse, Visibility};
+
+/// How private projects that aren't allowlisted are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivateProjects {
+    /// Omitted entirely.
+    #[default]
+    Hide,
+    /// Included, but only with non-identifying fields such as languages.
+    Redact,
+}
+
+/// A user's choice of how their private projects are published, enforced on the output of every
+/// plugin that includes data from repositories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyPolicy {
+    #[serde(default)]
+    pub private_projects: PrivateProjects,
+    /// Private projects that are published in full, by name including the owner, such as
+    /// `octocat/hello-world`.
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+}
+
+impl PrivacyPolicy {
+    pub fn from_user_source(user_source: &user_source::Model) -> Self {
+        Self {
+            // Anything unrecognised is treated as the most private option
+            private_projects: match user_source.private_repos.as_str() {
+                "redact" => PrivateProjects::Redact,
+                _ => PrivateProjects::Hide,
+            },
+            allowlist: serde_json::from_value(user_source.private_repo_allowlist.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn private_repos(&self) -> String {
+        match self.private_projects {
+            PrivateProjects::Hide => "hide",
+            PrivateProjects::Redact => "redact",
+        }
+        .to_string()
+    }
+
+    pub fn allowlist_json(&self) -> JsonValue {
+        JsonValue::from(self.allowlist.clone())
+    }
+
+    /// Private repositories are published in full if allowlisted, and otherwise hidden or
+    /// redacted as chosen.
+    fn visibility(&self, full_name: &str, private: bool) -> Visibility {
+        let allowlisted = self
+            .allowlist
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(full_name));
+
+        match (private && !allowlisted, self.private_projects) {
+            (false, _) => Visibility::Public,
+            (true, PrivateProjects::Hide) => Visibility::Hidden,
+            (true, PrivateProjects::Redact) => Visibility::Redacted,
+        }
+    }
+
+    /// Applies the policy to the output of a plugin, to every repository it includes data from.
+    /// A private project requested individually is only found if it is allowlisted.
+    pub fn enforce(&self, response: &mut PluginResponse) -> Result<(), PluginError> {
+        response.restrict(&|full_name, private| self.visibility(full_name, private))
+    }
+}
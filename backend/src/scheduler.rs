@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use shared::config::Section;
+use tokio::{sync::Semaphore, task, time};
+use tracing::{error, info, info_span, warn, Instrument};
+
+use crate::data::{DataService, MAX_AGE};
+
+/// How often the scheduler checks for jobs that are due.
+const TICK: Duration = Duration::from_secs(30);
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_CONCURRENCY: usize = 4;
+/// Maximum proportion of an interval that a job is randomly offset by, to spread out requests.
+const JITTER: f64 = 0.1;
+
+/// Identifies a job by request type, source, plugin and username.
+type JobKey = (String, String, String, String);
+
+pub struct SchedulerConfig {
+    default_interval: Duration,
+    /// Intervals for specific plugins, keyed by either `request_type` or
+    /// `request_type/source_identifier/plugin_identifier`.
+    intervals: HashMap<String, Duration>,
+    concurrency: usize,
+}
+
+impl SchedulerConfig {
+    /// Reads the `refresh` section: `interval` (seconds, `0` to disable), `intervals`
+    /// (overrides in seconds, `0` to disable a plugin) and `concurrency`. Returns `None` if
+    /// refreshing is disabled.
+    pub fn from_config(section: &Section) -> Option<Self> {
+        let default_interval = section
+            .optional("interval")
+            .map(|seconds| bounded(section, "interval", seconds))
+            .unwrap_or(DEFAULT_INTERVAL);
+        let intervals = section
+            .optional::<HashMap<String, u64>>("intervals")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, seconds)| (key, bounded(section, "intervals", seconds)))
+            .collect();
+        let concurrency = section
+            .optional::<usize>("concurrency")
+            .map(|concurrency| concurrency.max(1))
+            .unwrap_or(DEFAULT_CONCURRENCY);
+
+        (!default_interval.is_zero()).then_some(Self {
+            default_interval,
+            intervals,
+            concurrency,
+        })
+    }
+
+    /// Interval for a job, or `None` if its plugin isn't refreshed.
+    fn interval(
+        &self,
+        (request_type, source_identifier, plugin_identifier, _): &JobKey,
+    ) -> Option<Duration> {
+        let interval = self
+            .intervals
+            .get(&format!(
+                "{request_type}/{source_identifier}/{plugin_identifier}"
+            ))
+            .or_else(|| self.intervals.get(request_type))
+            .copied()
+            .unwrap_or(self.default_interval);
+
+        (!interval.is_zero()).then_some(interval)
+    }
+}
+
+/// Reads an interval in seconds. Intervals longer than results are stored for are reported, as
+/// results would expire before being refreshed.
+fn bounded(section: &Section, key: &str, seconds: u64) -> Duration {
+    let interval = Duration::from_secs(seconds);
+    if interval > MAX_AGE {
+        section.problem(
+            key,
+            format!("must be at most {} seconds", MAX_AGE.as_secs()),
+        );
+    }
+
+    interval.min(MAX_AGE)
+}
+
+/// Periodically runs every plugin for every linked user source, storing the results so that
+/// requests don't wait on the source.
+pub struct Scheduler {
+    data: Arc<DataService>,
+    config: SchedulerConfig,
+    next_runs: HashMap<JobKey, Instant>,
+    permits: Arc<Semaphore>,
+}
+
+impl Scheduler {
+    pub fn new(data: &Arc<DataService>, config: SchedulerConfig) -> Self {
+        Self {
+            data: Arc::clone(data),
+            permits: Arc::new(Semaphore::new(config.concurrency)),
+            config,
+            next_runs: HashMap::new(),
+        }
+    }
+
+    pub fn spawn(mut self) -> task::JoinHandle<()> {
+        task::spawn(async move {
+            let mut ticker = time::interval(TICK);
+
+            loop {
+                ticker.tick().await;
+                self.schedule_due().await;
+            }
+        })
+    }
+
+    fn jittered(&self, interval: Duration) -> Duration {
+        interval.mul_f64(1.0 + rand::thread_rng().gen_range(-JITTER..=JITTER))
+    }
+
+    async fn schedule_due(&mut self) {
+        let user_sources = match self.data.all_user_sources().await {
+            Ok(user_sources) => user_sources,
+            Err(e) => {
+                error!(message = "unable to load user sources for refresh", error = ?e);
+                return;
+            }
+        };
+
+        for user_source in user_sources {
+            for (request_type, plugin_identifier) in
+                self.data.refreshable_plugins(&user_source.site)
+            {
+                let key = (
+                    request_type,
+                    user_source.site.clone(),
+                    plugin_identifier,
+                    user_source.username.clone(),
+                );
+
+                let Some(interval) = self.config.interval(&key) else {
+                    continue;
+                };
+
+                let now = Instant::now();
+                match self.next_runs.get(&key) {
+                    // Spread first runs across the interval, rather than running every job at
+                    // once after a restart
+                    None => {
+                        let delay = interval.mul_f64(rand::thread_rng().gen_range(0.0..1.0));
+                        self.next_runs.insert(key, now + delay);
+                        continue;
+                    }
+                    Some(next_run) if *next_run > now => continue,
+                    Some(_) => {}
+                }
+                self.next_runs
+                    .insert(key.clone(), now + self.jittered(interval));
+
+                // Wait for capacity, so that the number of jobs in flight is limited
+                let Ok(permit) = Arc::clone(&self.permits).acquire_owned().await else {
+                    return;
+                };
+
+                let data = Arc::clone(&self.data);
+                let user_source = user_source.clone();
+                let (request_type, source, plugin, username) = key;
+                let span = info_span!("refresh job", request_type, source, plugin, username);
+
+                task::spawn(
+                    async move {
+                        let started = Instant::now();
+                        let result = data.refresh(&request_type, &plugin, &user_source).await;
+                        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+                        match result {
+                            Ok(()) => info!(message = "refreshed", elapsed_ms),
+                            Err(response) => warn!(
+                                message = "refresh failed",
+                                elapsed_ms,
+                                status = ?response.status()
+                            ),
+                        }
+
+                        drop(permit);
+                    }
+                    .instrument(span),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::{HashMap, HashSet};
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+/// BM25 term frequency saturation.
+const K1: f32 = 1.2;
+/// BM25 document length normalisation.
+const B: f32 = 0.75;
+/// Score added to a document for each phrase it contains.
+const PHRASE_BONUS: f32 = 2.0;
+/// Characters of context shown either side of the first match in a snippet.
+const SNIPPET_CONTEXT: usize = 80;
+/// Maximum length of a snippet, in bytes of source text.
+const SNIPPET_LENGTH: usize = 240;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Post,
+    Project,
+}
+
+#[derive(Debug, Hash)]
+pub struct Document {
+    pub kind: DocumentKind,
+    pub title: String,
+    /// Plain text content of the document.
+    pub body: String,
+    pub tags: Vec<String>,
+    pub url: Option<String>,
+    pub slug: Option<String>,
+}
+
+impl Document {
+    fn field(&self, field: Field) -> String {
+        match field {
+            Field::Title => self.title.clone(),
+            Field::Tags => self.tags.join(" "),
+            Field::Body => self.body.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Tags,
+    Body,
+}
+impl Field {
+    const ALL: [Field; 3] = [Field::Title, Field::Tags, Field::Body];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    fn boost(&self) -> f32 {
+        match self {
+            Field::Title => 3.0,
+            Field::Tags => 2.0,
+            Field::Body => 1.0,
+        }
+    }
+}
+
+struct Token {
+    term: String,
+    start: usize,
+    end: usize,
+}
+
+/// Splits text into lowercase alphanumeric terms, tracking their byte offsets.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push(Token {
+                    term: text[s..i].to_lowercase(),
+                    start: s,
+                    end: i,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Converts Markdown to plain text, so that syntax doesn't appear in snippets.
+pub fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item)
+            | Event::Start(Tag::CodeBlock(_)) => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// A parsed search query. Quoted text is treated as a phrase, and `tag:name` restricts results to
+/// documents with the tag.
+#[derive(Debug, Default)]
+pub struct Query {
+    terms: Vec<String>,
+    phrases: Vec<Vec<String>>,
+    tags: Vec<String>,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for (i, part) in query.split('"').enumerate() {
+            // Odd parts are within quotes
+            if i % 2 == 1 {
+                let phrase = tokenize(part)
+                    .into_iter()
+                    .map(|token| token.term)
+                    .collect::<Vec<_>>();
+                match phrase.len() {
+                    0 => {}
+                    1 => parsed.terms.extend(phrase),
+                    _ => parsed.phrases.push(phrase),
+                }
+
+                continue;
+            }
+
+            for word in part.split_whitespace() {
+                match word.strip_prefix("tag:") {
+                    Some(tag) => parsed.with_tag(tag),
+                    None => parsed
+                        .terms
+                        .extend(tokenize(word).into_iter().map(|token| token.term)),
+                }
+            }
+        }
+
+        parsed
+    }
+
+    pub fn with_tag(&mut self, tag: &str) {
+        if !tag.is_empty() {
+            self.tags.push(tag.to_lowercase());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty() && self.tags.is_empty()
+    }
+
+    /// Every term in the query, including those within phrases.
+    fn all_terms(&self) -> HashSet<&str> {
+        self.terms
+            .iter()
+            .chain(self.phrases.iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+struct Posting {
+    document: usize,
+    field: Field,
+    positions: Vec<usize>,
+}
+
+pub struct SearchHit<'a> {
+    pub document: &'a Document,
+    pub score: f32,
+    /// HTML snippet of the document, with matching terms wrapped in `<mark>`.
+    pub snippet: String,
+}
+
+pub struct SearchIndex {
+    documents: Vec<Document>,
+    postings: HashMap<String, Vec<Posting>>,
+    field_lengths: Vec<[usize; 3]>,
+    average_lengths: [f32; 3],
+}
+
+impl SearchIndex {
+    pub fn new(documents: Vec<Document>) -> Self {
+        let mut postings = HashMap::<String, Vec<Posting>>::new();
+        let mut field_lengths = Vec::with_capacity(documents.len());
+
+        for (document_id, document) in documents.iter().enumerate() {
+            let mut lengths = [0; 3];
+
+            for field in Field::ALL {
+                let tokens = tokenize(&document.field(field));
+                lengths[field.index()] = tokens.len();
+
+                let mut positions = HashMap::<String, Vec<usize>>::new();
+                for (position, token) in tokens.into_iter().enumerate() {
+                    positions.entry(token.term).or_default().push(position);
+                }
+
+                for (term, positions) in positions {
+                    postings.entry(term).or_default().push(Posting {
+                        document: document_id,
+                        field,
+                        positions,
+                    });
+                }
+            }
+
+            field_lengths.push(lengths);
+        }
+
+        let average_lengths = Field::ALL.map(|field| {
+            let total = field_lengths
+                .iter()
+                .map(|lengths| lengths[field.index()])
+                .sum::<usize>();
+            (total as f32 / documents.len().max(1) as f32).max(1.0)
+        });
+
+        Self {
+            documents,
+            postings,
+            field_lengths,
+            average_lengths,
+        }
+    }
+
+    fn positions(&self, term: &str, document: usize, field: Field) -> Option<&[usize]> {
+        self.postings.get(term)?.iter().find_map(|posting| {
+            (posting.document == document && posting.field == field)
+                .then_some(posting.positions.as_slice())
+        })
+    }
+
+    fn contains_phrase(&self, phrase: &[String], document: usize) -> bool {
+        Field::ALL.into_iter().any(|field| {
+            let Some(first) = self.positions(&phrase[0], document, field) else {
+                return false;
+            };
+
+            first.iter().any(|start| {
+                phrase.iter().enumerate().skip(1).all(|(offset, term)| {
+                    self.positions(term, document, field)
+                        .map(|positions| positions.contains(&(start + offset)))
+                        .unwrap_or(false)
+                })
+            })
+        })
+    }
+
+    /// BM25F score of a term for each document containing it.
+    fn score_term(&self, term: &str, scores: &mut HashMap<usize, f32>) {
+        let Some(postings) = self.postings.get(term) else {
+            return;
+        };
+
+        let document_frequency = postings
+            .iter()
+            .map(|posting| posting.document)
+            .collect::<HashSet<_>>()
+            .len() as f32;
+        let total = self.documents.len() as f32;
+        let idf = ((total - document_frequency + 0.5) / (document_frequency + 0.5) + 1.0).ln();
+
+        for posting in postings {
+            let field = posting.field;
+            let length = self.field_lengths[posting.document][field.index()] as f32;
+            let frequency = posting.positions.len() as f32;
+            let normalised = frequency
+                / (frequency + K1 * (1.0 - B + B * length / self.average_lengths[field.index()]));
+
+            *scores.entry(posting.document).or_default() +=
+                idf * field.boost() * normalised * (K1 + 1.0);
+        }
+    }
+
+    pub fn search(&self, query: &Query, limit: usize) -> Vec<SearchHit<'_>> {
+        let mut scores = HashMap::new();
+        for term in query.all_terms() {
+            self.score_term(term, &mut scores);
+        }
+
+        // A query of only tags matches every document, which is then filtered by tag
+        if query.terms.is_empty() && query.phrases.is_empty() {
+            scores = (0..self.documents.len()).map(|id| (id, 0.0)).collect();
+        }
+
+        let mut hits = scores
+            .into_iter()
+            .filter(|(id, _)| {
+                let document = &self.documents[*id];
+                query.tags.iter().all(|tag| {
+                    document
+                        .tags
+                        .iter()
+                        .any(|document_tag| document_tag.to_lowercase() == *tag)
+                })
+            })
+            .filter_map(|(id, score)| {
+                let phrases = query
+                    .phrases
+                    .iter()
+                    .filter(|phrase| self.contains_phrase(phrase, id))
+                    .count();
+
+                // Every phrase must be present
+                (phrases == query.phrases.len())
+                    .then_some((id, score + phrases as f32 * PHRASE_BONUS))
+            })
+            .collect::<Vec<_>>();
+
+        hits.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then_with(|| a_id.cmp(b_id)));
+
+        let terms = query.all_terms();
+        hits.into_iter()
+            .take(limit)
+            .map(|(id, score)| {
+                let document = &self.documents[id];
+
+                SearchHit {
+                    document,
+                    score,
+                    snippet: snippet(&document.body, &terms),
+                }
+            })
+            .collect()
+    }
+}
+
+fn escape(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            c => output.push(c),
+        }
+    }
+}
+
+/// Extracts a window of text around the first matching term, highlighting every match within it.
+fn snippet(text: &str, terms: &HashSet<&str>) -> String {
+    let tokens = tokenize(text);
+    if tokens.is_empty() {
+        return String::new();
+    }
+
+    let first_match = tokens
+        .iter()
+        .find(|token| terms.contains(token.term.as_str()))
+        .unwrap_or(&tokens[0]);
+
+    // Align the window to token boundaries
+    let start = tokens
+        .iter()
+        .map(|token| token.start)
+        .find(|start| *start + SNIPPET_CONTEXT >= first_match.start)
+        .unwrap_or(0);
+    // The window always includes the first match, even if it is longer than the snippet
+    let end = tokens
+        .iter()
+        .map(|token| token.end)
+        .take_while(|end| *end <= start + SNIPPET_LENGTH)
+        .last()
+        .unwrap_or(tokens[0].end)
+        .max(first_match.end);
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    let mut offset = start;
+    for token in tokens
+        .iter()
+        .filter(|token| token.start >= start && token.end <= end)
+        .filter(|token| terms.contains(token.term.as_str()))
+    {
+        escape(&text[offset..token.start], &mut snippet);
+        snippet.push_str("<mark>");
+        escape(&text[token.start..token.end], &mut snippet);
+        snippet.push_str("</mark>");
+        offset = token.end;
+    }
+    escape(&text[offset..end], &mut snippet);
+
+    if text[end..].chars().any(char::is_alphanumeric) {
+        snippet.push('…');
+    }
+
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
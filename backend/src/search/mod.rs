@@ -0,0 +1,247 @@
+mod index;
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{http::StatusCode, response::IntoResponse, response::Response};
+use serde::{Deserialize, Serialize};
+use shared::plugin::{PluginResponse, PostsResponse, ProjectsResponse};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use index::{plain_text, Document, DocumentKind, Query, SearchIndex};
+
+use crate::data::DataService;
+
+/// How long an index is used before the underlying data is fetched again to check for changes.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    q: String,
+    tag: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    kind: DocumentKind,
+    title: String,
+    url: Option<String>,
+    slug: Option<String>,
+    tags: Vec<String>,
+    score: f32,
+    snippet: String,
+}
+
+#[derive(Serialize)]
+pub struct SearchResponse {
+    query: String,
+    results: Vec<SearchResult>,
+}
+
+#[derive(Clone)]
+struct UserIndex {
+    index: Arc<SearchIndex>,
+    fingerprint: u64,
+    refreshed_at: Instant,
+}
+
+/// Full-text search over the posts and projects of each user, built from the output of their
+/// plugins and kept in memory.
+pub struct Search {
+    data: Arc<DataService>,
+    indexes: Mutex<HashMap<String, UserIndex>>,
+}
+
+impl Search {
+    pub fn new(data: &Arc<DataService>) -> Self {
+        Self {
+            data: Arc::clone(data),
+            indexes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn search(
+        &self,
+        username: &str,
+        params: SearchParams,
+    ) -> Result<SearchResponse, Response> {
+        let mut query = Query::parse(&params.q);
+        if let Some(tag) = &params.tag {
+            query.with_tag(tag);
+        }
+
+        if query.is_empty() {
+            return Err(StatusCode::BAD_REQUEST.into_response());
+        }
+
+        let index = self.get_index(username).await?;
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        Ok(SearchResponse {
+            results: index
+                .search(&query, limit)
+                .into_iter()
+                .map(|hit| SearchResult {
+                    kind: hit.document.kind,
+                    title: hit.document.title.clone(),
+                    url: hit.document.url.clone(),
+                    slug: hit.document.slug.clone(),
+                    tags: hit.document.tags.clone(),
+                    score: hit.score,
+                    snippet: hit.snippet,
+                })
+                .collect(),
+            query: params.q,
+        })
+    }
+
+    /// Gets the index for a user, rebuilding it if the underlying data has changed.
+    async fn get_index(&self, username: &str) -> Result<Arc<SearchIndex>, Response> {
+        if let Some(index) = self.indexes.lock().await.get(username) {
+            if index.refreshed_at.elapsed() < REFRESH_INTERVAL {
+                return Ok(Arc::clone(&index.index));
+            }
+        }
+
+        let documents = self.collect_documents(username).await?;
+        let fingerprint = {
+            let mut hasher = DefaultHasher::new();
+            documents.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut indexes = self.indexes.lock().await;
+        let index = match indexes.get(username) {
+            // Data is unchanged, so the existing index can be reused
+            Some(index) if index.fingerprint == fingerprint => Arc::clone(&index.index),
+            _ => {
+                info!(message = "rebuilding search index", username);
+                Arc::new(SearchIndex::new(documents))
+            }
+        };
+        indexes.insert(
+            username.to_string(),
+            UserIndex {
+                index: Arc::clone(&index),
+                fingerprint,
+                refreshed_at: Instant::now(),
+            },
+        );
+
+        Ok(index)
+    }
+
+    /// Runs every posts and projects plugin for each of the user's sources, converting the
+    /// results into documents.
+    async fn collect_documents(&self, username: &str) -> Result<Vec<Document>, Response> {
+        let user_sources = self.data.user_sources(username).await?;
+        if user_sources.is_empty() {
+            return Err(StatusCode::UNAUTHORIZED.into_response());
+        }
+
+        let mut documents = Vec::new();
+        let mut seen = Vec::new();
+
+        for user_source in user_sources {
+            for request_type in ["posts", "projects"] {
+                for plugin_identifier in self.data.plugins_for(request_type, &user_source.site) {
+                    // A failing plugin leaves its documents out rather than failing the search
+                    let response = match self
+                        .data
+                        .get(
+                            request_type,
+                            &user_source.site,
+                            &plugin_identifier,
+                            username,
+                            HashMap::new(),
+                            None,
+                        )
+                        .await
+                    {
+                        Ok(fetched) => fetched.response,
+                        Err(response) => {
+                            warn!(
+                                message = "unable to collect documents",
+                                request_type,
+                                plugin = plugin_identifier,
+                                status = ?response.status()
+                            );
+                            continue;
+                        }
+                    };
+
+                    for document in to_documents(&response) {
+                        // Multiple plugins may return the same item
+                        let key = (document.kind, document.title.clone(), document.url.clone());
+                        if !seen.contains(&key) {
+                            seen.push(key);
+                            documents.push(document);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+fn to_documents(response: &PluginResponse) -> Vec<Document> {
+    if let Some(posts) = response.downcast_ref::<PostsResponse>() {
+        return posts
+            .iter()
+            .map(|post| Document {
+                kind: DocumentKind::Post,
+                title: post.title.clone(),
+                body: plain_text(&post.body),
+                tags: post.tags.clone(),
+                url: Some(
+                    post.canonical_url
+                        .clone()
+                        .unwrap_or_else(|| post.original_link.clone()),
+                ),
+                slug: Some(post.slug.clone()),
+            })
+            .collect();
+    }
+
+    if let Some(projects) = response.downcast_ref::<ProjectsResponse>() {
+        return projects
+            .iter()
+            .map(|project| Document {
+                kind: DocumentKind::Project,
+                title: project.name.clone(),
+                body: [
+                    project.description.clone(),
+                    project.languages.as_ref().map(|languages| {
+                        languages
+                            .iter()
+                            .map(|language| language.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    }),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n"),
+                tags: project.tags.clone(),
+                url: project
+                    .url
+                    .clone()
+                    .or_else(|| project.repo.as_ref().map(|repo| repo.url.clone())),
+                slug: None,
+            })
+            .collect();
+    }
+
+    Vec::new()
+}
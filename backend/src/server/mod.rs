@@ -0,0 +1,211 @@
+mod tls;
+
+use std::{
+    fs,
+    future::{ready, Future},
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    os::unix::fs::FileTypeExt,
+    path::PathBuf,
+    time::Duration,
+};
+
+use axum::Router;
+use futures::{stream, FutureExt, StreamExt};
+use hyper::server::{accept, Server};
+use shared::config::Section;
+use tokio::{
+    net::{TcpListener, UnixListener},
+    signal::{
+        self,
+        unix::{signal, SignalKind},
+    },
+    time,
+};
+use tracing::{debug, error, info, warn};
+
+use crate::BackendError;
+
+use tls::TlsConfig;
+
+const DEFAULT_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum number of TLS handshakes in progress at once.
+const HANDSHAKE_CONCURRENCY: usize = 64;
+/// Time a client has to complete a TLS handshake before its connection is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ServerConfig {
+    address: SocketAddr,
+    /// Unix socket listened on instead of the address.
+    socket: Option<PathBuf>,
+    tls: Option<TlsConfig>,
+    /// Time requests in progress have to finish once the server is asked to stop.
+    shutdown_timeout: Duration,
+}
+
+impl ServerConfig {
+    /// Reads the `server` section: `address`, `port`, `socket`, `shutdown_timeout` (seconds)
+    /// and the `tls` section.
+    pub fn from_config(section: &Section) -> Self {
+        let address = section.optional("address").unwrap_or(DEFAULT_ADDRESS);
+        let port = section.optional("port").unwrap_or(DEFAULT_PORT);
+        let socket = section.optional("socket");
+        let shutdown_timeout = section
+            .optional("shutdown_timeout")
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+        let tls = TlsConfig::from_config(&section.section("tls"));
+
+        if socket.is_some() && tls.is_some() {
+            section.problem("socket", "TLS is not supported on Unix sockets");
+        }
+
+        Self {
+            address: SocketAddr::new(address, port),
+            socket,
+            tls,
+            shutdown_timeout,
+        }
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        self.shutdown_timeout
+    }
+}
+
+/// Completes when the process is asked to stop, by Ctrl+C or `SIGTERM`.
+pub async fn shutdown_signal() {
+    let terminate = async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                error!(message = "unable to listen for SIGTERM", error = ?e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = signal::ctrl_c() => {},
+        _ = terminate => {},
+    }
+    info!("Shutting down");
+}
+
+/// Waits for a server to stop, giving up once the timeout has passed after `shutdown` completes.
+async fn drain(
+    server: impl Future<Output = Result<(), hyper::Error>>,
+    shutdown: impl Future<Output = ()>,
+    timeout: Duration,
+) -> Result<(), BackendError> {
+    tokio::select! {
+        result = server => result?,
+        _ = async {
+            shutdown.await;
+            time::sleep(timeout).await;
+        } => warn!(
+            message = "requests still in progress after shutdown timeout",
+            timeout_secs = timeout.as_secs()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Serves requests until `shutdown` completes, then stops accepting connections and waits for
+/// requests in progress to finish, for up to the shutdown timeout.
+pub async fn serve(
+    router: Router,
+    config: ServerConfig,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), BackendError> {
+    let service = router.into_make_service();
+    let shutdown = shutdown.shared();
+    let timeout = config.shutdown_timeout;
+
+    match (config.socket, config.tls) {
+        (Some(path), _) => {
+            // Remove a socket left behind by a previous run, but never any other kind of file
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(&path)?,
+                Ok(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("{} exists and is not a socket", path.display()),
+                    )
+                    .into())
+                }
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                Err(_) => {}
+            }
+            let listener = UnixListener::bind(&path)?;
+            info!("Listening on {}", path.display());
+
+            let server = Server::builder(accept::poll_fn(move |cx| {
+                listener
+                    .poll_accept(cx)
+                    .map(|connection| Some(connection.map(|(stream, _)| stream)))
+            }))
+            .serve(service)
+            .with_graceful_shutdown(shutdown.clone());
+            drain(server, shutdown, timeout).await?;
+        }
+        (None, Some(tls)) => {
+            let acceptor = tls::acceptor(tls)?;
+            let listener = TcpListener::bind(config.address).await?;
+            info!("Listening on https://{}", config.address);
+
+            let mut connections = Box::pin(
+                stream::unfold(listener, |listener| async {
+                    let connection = listener.accept().await;
+                    Some((connection, listener))
+                })
+                .filter_map(|connection| {
+                    ready(
+                        connection
+                            .map_err(
+                                |e| error!(message = "unable to accept connection", error = ?e),
+                            )
+                            .ok(),
+                    )
+                })
+                .map(move |(stream, _)| time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)))
+                .buffer_unordered(HANDSHAKE_CONCURRENCY)
+                .filter_map(|handshake| {
+                    ready(match handshake {
+                        Ok(Ok(stream)) => Some(Ok::<_, io::Error>(stream)),
+                        Ok(Err(e)) => {
+                            debug!(message = "TLS handshake failed", error = ?e);
+                            None
+                        }
+                        Err(_) => {
+                            debug!("TLS handshake timed out");
+                            None
+                        }
+                    })
+                }),
+            );
+
+            let server =
+                Server::builder(accept::poll_fn(move |cx| connections.poll_next_unpin(cx)))
+                    .serve(service)
+                    .with_graceful_shutdown(shutdown.clone());
+            drain(server, shutdown, timeout).await?;
+        }
+        (None, None) => {
+            let server = Server::try_bind(&config.address)?;
+            info!("Listening on http://{}", config.address);
+
+            let server = server
+                .serve(service)
+                .with_graceful_shutdown(shutdown.clone());
+            drain(server, shutdown, timeout).await?;
+        }
+    }
+
+    Ok(())
+}
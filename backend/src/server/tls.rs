@@ -0,0 +1,177 @@
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use rustls_pemfile::Item;
+use shared::config::Section;
+use tokio::{task, time};
+use tokio_rustls::{
+    rustls::{
+        server::{ClientHello, ResolvesServerCert},
+        sign::{self, CertifiedKey},
+        Certificate, PrivateKey, ServerConfig,
+    },
+    TlsAcceptor,
+};
+use tracing::{error, info};
+
+const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct TlsConfig {
+    /// PEM file containing the certificate chain.
+    certificate: PathBuf,
+    /// PEM file containing the private key.
+    private_key: PathBuf,
+    /// How often the files are checked for changes, so that renewed certificates are used
+    /// without a restart, or `None` if they aren't.
+    reload_interval: Option<Duration>,
+}
+
+impl TlsConfig {
+    /// Reads the `tls` section: `certificate`, `private_key` and `reload_interval` (seconds, `0`
+    /// to disable). Returns `None` if TLS is disabled, when neither file is given.
+    pub fn from_config(section: &Section) -> Option<Self> {
+        let certificate = section.optional("certificate");
+        let private_key = section.optional("private_key");
+        let reload_interval = section
+            .optional("reload_interval")
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_RELOAD_INTERVAL);
+        let reload_interval = (!reload_interval.is_zero()).then_some(reload_interval);
+
+        match (certificate, private_key) {
+            (Some(certificate), Some(private_key)) => Some(Self {
+                certificate,
+                private_key,
+                reload_interval,
+            }),
+            (Some(_), None) => {
+                section.problem("private_key", "required when a certificate is given");
+                None
+            }
+            (None, Some(_)) => {
+                section.problem("certificate", "required when a private key is given");
+                None
+            }
+            (None, None) => None,
+        }
+    }
+
+    fn read_pem(path: &PathBuf) -> io::Result<Vec<Item>> {
+        rustls_pemfile::read_all(&mut BufReader::new(File::open(path)?))
+    }
+
+    fn load(&self) -> io::Result<CertifiedKey> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let certificates = Self::read_pem(&self.certificate)?
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::X509Certificate(certificate) => Some(Certificate(certificate)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if certificates.is_empty() {
+            return Err(invalid(format!(
+                "no certificates found in {}",
+                self.certificate.display()
+            )));
+        }
+
+        let private_key = Self::read_pem(&self.private_key)?
+            .into_iter()
+            .find_map(|item| match item {
+                Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                invalid(format!(
+                    "no private key found in {}",
+                    self.private_key.display()
+                ))
+            })?;
+        let signing_key =
+            sign::any_supported_type(&private_key).map_err(|e| invalid(e.to_string()))?;
+
+        Ok(CertifiedKey::new(certificates, signing_key))
+    }
+
+    /// Most recent modification time of either file.
+    fn modified(&self) -> Option<SystemTime> {
+        [&self.certificate, &self.private_key]
+            .into_iter()
+            .filter_map(|path| {
+                path.metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .max()
+    }
+}
+
+/// Serves the most recently loaded certificate, replacing it when the files change.
+struct ReloadingCertificate {
+    config: TlsConfig,
+    key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ResolvesServerCert for ReloadingCertificate {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        self.key.read().ok().map(|key| Arc::clone(&key))
+    }
+}
+
+impl ReloadingCertificate {
+    fn spawn_reload(self: Arc<Self>, reload_interval: Duration) {
+        task::spawn(async move {
+            let mut loaded = self.config.modified();
+            let mut ticker = time::interval(reload_interval);
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+
+                let modified = self.config.modified();
+                if modified <= loaded {
+                    continue;
+                }
+
+                // A failed reload, such as when only one file has been replaced so far, keeps
+                // the previous certificate and is tried again on the next tick
+                match self.config.load() {
+                    Ok(key) => {
+                        if let Ok(mut current) = self.key.write() {
+                            *current = Arc::new(key);
+                        }
+                        loaded = modified;
+                        info!("Reloaded TLS certificate");
+                    }
+                    Err(e) => error!(message = "unable to reload TLS certificate", error = ?e),
+                }
+            }
+        });
+    }
+}
+
+/// Loads the certificate, then starts checking it for changes if enabled.
+pub fn acceptor(config: TlsConfig) -> io::Result<TlsAcceptor> {
+    let reload_interval = config.reload_interval;
+    let certificate = Arc::new(ReloadingCertificate {
+        key: RwLock::new(Arc::new(config.load()?)),
+        config,
+    });
+    if let Some(reload_interval) = reload_interval {
+        Arc::clone(&certificate).spawn_reload(reload_interval);
+    }
+
+    Ok(TlsAcceptor::from(Arc::new(
+        ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_cert_resolver(certificate),
+    )))
+}
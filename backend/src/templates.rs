@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use axum::http::{header, HeaderMap};
+use minijinja::{path_loader, Environment, ErrorKind as TemplateErrorKind};
+use serde::{Deserialize, Serialize};
+use shared::plugin::PluginResponse;
+use thiserror::Error;
+
+/// File within the template directory that maps page routes to templates.
+const ROUTES_FILE: &str = "routes.json";
+
+/// Directory within the template directory containing fragments for `/api` responses.
+const FRAGMENT_DIRECTORY: &str = "api";
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("unable to read template routes: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unable to parse template routes: {0}")]
+    Routes(#[from] serde_json::Error),
+    #[error("template error: {0}")]
+    Template(#[from] minijinja::Error),
+}
+
+/// Reference to the plugin that fills a value in a template context, in the form
+/// `request_type/source_identifier/plugin_identifier`.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct DataReference {
+    pub request_type: String,
+    pub source_identifier: String,
+    pub plugin_identifier: String,
+}
+impl TryFrom<String> for DataReference {
+    type Error = String;
+
+    fn try_from(reference: String) -> Result<Self, Self::Error> {
+        match reference.split('/').collect::<Vec<_>>()[..] {
+            [request_type, source_identifier, plugin_identifier] => Ok(Self {
+                request_type: request_type.to_string(),
+                source_identifier: source_identifier.to_string(),
+                plugin_identifier: plugin_identifier.to_string(),
+            }),
+            _ => Err(format!(
+                "expected `request_type/source/plugin`, found `{reference}`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageRoute {
+    pub template: String,
+    #[serde(default)]
+    pub data: HashMap<String, DataReference>,
+}
+
+#[derive(Serialize)]
+struct PageContext<'a> {
+    username: &'a str,
+    path: &'a str,
+    #[serde(flatten)]
+    data: HashMap<String, Arc<PluginResponse>>,
+}
+
+#[derive(Serialize)]
+struct FragmentContext<'a> {
+    username: &'a str,
+    request_type: &'a str,
+    source: &'a str,
+    plugin: &'a str,
+    data: &'a PluginResponse,
+}
+
+pub struct Templates {
+    environment: Environment<'static>,
+    routes: HashMap<String, PageRoute>,
+}
+
+impl Templates {
+    /// Loads templates from the given directory, validating that every template referenced by
+    /// the routes file exists.
+    pub fn load(directory: &Path) -> Result<Self, TemplateError> {
+        let mut environment = Environment::new();
+        environment.set_loader(path_loader(PathBuf::from(directory)));
+
+        let routes: HashMap<String, PageRoute> =
+            match fs::read_to_string(directory.join(ROUTES_FILE)) {
+                Ok(routes) => serde_json::from_str(&routes)?,
+                Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+
+        for route in routes.values() {
+            environment.get_template(&route.template)?;
+        }
+
+        Ok(Self {
+            environment,
+            routes,
+        })
+    }
+
+    pub fn get_route(&self, path: &str) -> Option<&PageRoute> {
+        self.routes.get(path)
+    }
+
+    pub fn render_page(
+        &self,
+        route: &PageRoute,
+        username: &str,
+        path: &str,
+        data: HashMap<String, Arc<PluginResponse>>,
+    ) -> Result<String, TemplateError> {
+        Ok(self
+            .environment
+            .get_template(&route.template)?
+            .render(PageContext {
+                username,
+                path,
+                data,
+            })?)
+    }
+
+    /// Renders the fragment for a request type, if the template directory contains one.
+    pub fn render_fragment(
+        &self,
+        request_type: &str,
+        source: &str,
+        plugin: &str,
+        username: &str,
+        data: &PluginResponse,
+    ) -> Option<Result<String, TemplateError>> {
+        let template = match self
+            .environment
+            .get_template(&format!("{FRAGMENT_DIRECTORY}/{request_type}.html"))
+        {
+            Ok(template) => template,
+            Err(e) if e.kind() == TemplateErrorKind::TemplateNotFound => return None,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        Some(
+            template
+                .render(FragmentContext {
+                    username,
+                    request_type,
+                    source,
+                    plugin,
+                    data,
+                })
+                .map_err(TemplateError::from),
+        )
+    }
+}
+
+/// Determines whether the `Accept` header prefers HTML over JSON, respecting quality values.
+pub fn prefers_html(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+    else {
+        return false;
+    };
+
+    let quality = |media_type: &str| {
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let range = parts.next()?;
+                let q = parts
+                    .find_map(|parameter| parameter.strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                (range == media_type).then_some(q)
+            })
+            .fold(0.0, f32::max)
+    };
+
+    let html = quality("text/html");
+    html > 0.0 && html > quality("application/json")
+}